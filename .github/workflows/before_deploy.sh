@@ -15,10 +15,13 @@ main() {
 
     if [ "$OS_NAME" = "ubuntu-latest" ]; then
         cp target/$TARGET/max-opt/auto-splitting-ide $stage/auto-splitting-ide
+        cp target/$TARGET/max-opt/fake-game $stage/fake-game
     elif [[ "$OS_NAME" =~ ^macos\-.*$ ]]; then
         cp target/$TARGET/max-opt/auto-splitting-ide $stage/auto-splitting-ide
+        cp target/$TARGET/max-opt/fake-game $stage/fake-game
     elif [ "$OS_NAME" = "windows-latest" ]; then
         cp target/$TARGET/max-opt/auto-splitting-ide.exe $stage/auto-splitting-ide.exe
+        cp target/$TARGET/max-opt/fake-game.exe $stage/fake-game.exe
     fi
 
     cd $stage
@@ -0,0 +1,158 @@
+//! A fake game for testing auto splitters without owning or running the actual
+//! game. It keeps its state at a known memory layout and changes it based on
+//! commands it receives on stdin, one per line:
+//!
+//! - `level <u32>`
+//! - `loading <0|1>`
+//! - `paused <0|1>`
+//! - `health <f32>`
+//! - `score <u32>`
+//! - `reset`
+//!
+//! The state lives in the `STATE` static of the main module, which starts with
+//! the `SIGNATURE` so it can be found with a signature scan. All the fields are
+//! little endian:
+//!
+//! | Offset | Type  | Field                                          |
+//! | ------ | ----- | ---------------------------------------------- |
+//! | 0x00   | [u8]  | `ASIDE_FAKE_GAME\0`                            |
+//! | 0x10   | u32   | Level                                          |
+//! | 0x14   | u32   | Is loading (0 or 1)                            |
+//! | 0x18   | u32   | Is paused (0 or 1)                             |
+//! | 0x1C   | f32   | Health                                         |
+//! | 0x20   | u64   | Game time in milliseconds, stops while loading |
+//! | 0x28   | usize | Pointer to the player                          |
+//!
+//! The player is allocated on the heap and holds a pointer to its stats at
+//! offset 0x08. The stats hold the number of deaths as a u32 at offset 0x00
+//! and the score as a u32 at offset 0x04. So the score is at the pointer path
+//! `STATE + 0x28 -> 0x08 -> 0x04`.
+
+use std::{
+    io::{self, BufRead},
+    sync::atomic::{AtomicPtr, AtomicU32, AtomicU64, Ordering},
+    thread,
+    time::Duration,
+};
+
+const SIGNATURE: [u8; 16] = *b"ASIDE_FAKE_GAME\0";
+
+/// How often the game time advances.
+const FRAME_TIME: Duration = Duration::from_millis(16);
+
+// Some of the fields are only there for the auto splitter to read.
+#[allow(dead_code)]
+#[repr(C)]
+struct State {
+    signature: [u8; 16],
+    level: AtomicU32,
+    is_loading: AtomicU32,
+    is_paused: AtomicU32,
+    health: AtomicU32,
+    game_time_ms: AtomicU64,
+    player: AtomicPtr<Player>,
+}
+
+#[allow(dead_code)]
+#[repr(C)]
+struct Player {
+    id: u32,
+    stats: AtomicPtr<Stats>,
+}
+
+#[repr(C)]
+struct Stats {
+    deaths: AtomicU32,
+    score: AtomicU32,
+}
+
+const INITIAL_HEALTH: f32 = 100.0;
+
+#[no_mangle]
+static STATE: State = State {
+    signature: SIGNATURE,
+    level: AtomicU32::new(0),
+    is_loading: AtomicU32::new(0),
+    is_paused: AtomicU32::new(0),
+    health: AtomicU32::new(INITIAL_HEALTH.to_bits()),
+    game_time_ms: AtomicU64::new(0),
+    player: AtomicPtr::new(std::ptr::null_mut()),
+};
+
+fn main() {
+    let stats = Box::leak(Box::new(Stats {
+        deaths: AtomicU32::new(0),
+        score: AtomicU32::new(0),
+    }));
+    let player = Box::leak(Box::new(Player {
+        id: 1,
+        stats: AtomicPtr::new(stats),
+    }));
+    STATE.player.store(player, Ordering::Relaxed);
+
+    println!(
+        "Fake game started with PID {}. The state is at {:p}, the player at {:p} and \
+        the stats at {:p}.",
+        std::process::id(),
+        &STATE,
+        player,
+        stats,
+    );
+
+    thread::spawn(|| loop {
+        thread::sleep(FRAME_TIME);
+        if STATE.is_loading.load(Ordering::Relaxed) == 0
+            && STATE.is_paused.load(Ordering::Relaxed) == 0
+        {
+            STATE
+                .game_time_ms
+                .fetch_add(FRAME_TIME.as_millis() as u64, Ordering::Relaxed);
+        }
+    });
+
+    // The game quits once the IDE closes stdin.
+    for line in io::stdin().lock().lines().map_while(Result::ok) {
+        if let Err(e) = run_command(&line, stats) {
+            eprintln!("Invalid command `{line}`: {e}");
+        }
+    }
+}
+
+fn run_command(line: &str, stats: &Stats) -> Result<(), String> {
+    let mut parts = line.split_whitespace();
+    let command = parts.next().ok_or("The command is empty.")?;
+    let mut value = || parts.next().ok_or("The value is missing.");
+    match command {
+        "level" => STATE.level.store(parse(value()?)?, Ordering::Relaxed),
+        "loading" => STATE.is_loading.store(parse(value()?)?, Ordering::Relaxed),
+        "paused" => STATE.is_paused.store(parse(value()?)?, Ordering::Relaxed),
+        "health" => {
+            let health: f32 = parse(value()?)?;
+            let previous = f32::from_bits(STATE.health.load(Ordering::Relaxed));
+            if health <= 0.0 && previous > 0.0 {
+                stats.deaths.fetch_add(1, Ordering::Relaxed);
+            }
+            STATE.health.store(health.to_bits(), Ordering::Relaxed);
+        }
+        "score" => stats.score.store(parse(value()?)?, Ordering::Relaxed),
+        "reset" => {
+            STATE.level.store(0, Ordering::Relaxed);
+            STATE.is_loading.store(0, Ordering::Relaxed);
+            STATE.is_paused.store(0, Ordering::Relaxed);
+            STATE
+                .health
+                .store(INITIAL_HEALTH.to_bits(), Ordering::Relaxed);
+            STATE.game_time_ms.store(0, Ordering::Relaxed);
+            stats.deaths.store(0, Ordering::Relaxed);
+            stats.score.store(0, Ordering::Relaxed);
+        }
+        _ => return Err("Unknown command.".into()),
+    }
+    Ok(())
+}
+
+fn parse<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("`{value}` is not a valid value."))
+}
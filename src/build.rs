@@ -0,0 +1,222 @@
+//! Builds auto splitters from their Cargo project and rebuilds them whenever
+//! their sources change.
+
+use std::{
+    fs,
+    io::{BufRead, BufReader},
+    path::{Component, Path, PathBuf},
+    process::{Command, Stdio},
+    sync::mpsc,
+    thread,
+    time::SystemTime,
+};
+
+use anyhow::{bail, Context, Result};
+use livesplit_auto_splitting::LogLevel;
+use notify::{EventKind, RecursiveMode};
+
+use crate::file_watcher::{self, DebouncedWatcher};
+
+pub const DEFAULT_COMMAND: &str = "cargo build --target wasm32-wasip1";
+
+/// Watches the sources of a Cargo project. Dropping it stops watching.
+pub struct ProjectWatcher {
+    watcher: DebouncedWatcher,
+}
+
+impl ProjectWatcher {
+    /// Watches the project in the directory and builds it right away and
+    /// whenever the sources change. The messages of the build are passed to
+    /// `on_message` and the path of every successfully built auto splitter is
+    /// passed to `on_built`, unless the watcher got dropped during the build.
+    pub fn new(
+        dir: PathBuf,
+        command: String,
+        on_message: impl FnMut(String, LogLevel) + Clone + Send + 'static,
+        mut on_built: impl FnMut(PathBuf) + Send + 'static,
+    ) -> Result<Self> {
+        let target_dir = dir.join("target");
+        let watcher = DebouncedWatcher::new(
+            &dir.clone(),
+            RecursiveMode::Recursive,
+            move |event| match event.kind {
+                EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_) => {
+                    event.paths.iter().any(|path| is_source(path, &target_dir))
+                }
+                _ => event.need_rescan(),
+            },
+            {
+                let mut on_message = on_message.clone();
+                move |cancellation| {
+                    on_message(format!("Building with `{command}`."), LogLevel::Info);
+                    let result = build(&dir, &command, &mut on_message);
+                    if cancellation.is_cancelled() {
+                        return;
+                    }
+                    match result {
+                        Ok(path) => on_built(path),
+                        Err(e) => on_message(format!("{e:?}"), LogLevel::Error),
+                    }
+                }
+            },
+            file_watcher::log_errors(on_message),
+        )?;
+        watcher.trigger();
+
+        Ok(Self { watcher })
+    }
+
+    /// Builds the project again, even if the sources didn't change.
+    pub fn rebuild(&self) {
+        self.watcher.trigger();
+    }
+}
+
+/// Changes to the build output, the lock file that Cargo writes and hidden
+/// directories like `.git` don't cause a rebuild.
+fn is_source(path: &Path, target_dir: &Path) -> bool {
+    !path.starts_with(target_dir)
+        && path.file_name().is_some_and(|name| name != "Cargo.lock")
+        && !path.components().any(|component| {
+            matches!(component, Component::Normal(name) if name.to_string_lossy().starts_with('.'))
+        })
+}
+
+/// Runs the build command in the directory and returns the path of the
+/// WebAssembly file that it produced. The command is split at whitespace. If
+/// it is a Cargo command, Cargo's JSON messages are used to forward the
+/// compiler diagnostics and to find the produced file.
+pub fn build(
+    dir: &Path,
+    command: &str,
+    on_message: &mut impl FnMut(String, LogLevel),
+) -> Result<PathBuf> {
+    let mut args = command.split_whitespace();
+    let Some(program) = args.next() else {
+        bail!("The build command is empty.");
+    };
+    let mut command = Command::new(program);
+    command.args(args).current_dir(dir);
+    if program == "cargo"
+        && !command
+            .get_args()
+            .any(|arg| arg.to_string_lossy().starts_with("--message-format"))
+    {
+        command.arg("--message-format=json");
+    }
+
+    let started = SystemTime::now();
+    let mut child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .stdin(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed running `{program}`."))?;
+
+    let stdout = child.stdout.take().unwrap();
+    let stderr = child.stderr.take().unwrap();
+    let (sender, messages) = mpsc::channel();
+    let mut artifact = None;
+
+    thread::scope(|s| {
+        s.spawn({
+            let sender = sender.clone();
+            move || {
+                for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                    let level = if line.trim_start().starts_with("error") {
+                        LogLevel::Error
+                    } else if line.trim_start().starts_with("warning") {
+                        LogLevel::Warning
+                    } else {
+                        LogLevel::Debug
+                    };
+                    let _ = sender.send((line, level));
+                }
+            }
+        });
+        s.spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                let _ = sender.send((line, LogLevel::Info));
+            }
+        });
+
+        // The messages are forwarded on this thread, as the callback doesn't
+        // need to be shareable this way.
+        for (line, level) in messages {
+            match serde_json::from_str::<serde_json::Value>(&line) {
+                Ok(message) => {
+                    if let Some(path) = handle_cargo_message(&message, on_message) {
+                        artifact = Some(path);
+                    }
+                }
+                Err(_) => on_message(line, level),
+            }
+        }
+    });
+
+    let status = child.wait().context("Failed waiting for the build.")?;
+    if !status.success() {
+        bail!("The build failed ({status}).");
+    }
+
+    match artifact {
+        Some(path) => Ok(path),
+        None => newest_wasm_file(&dir.join("target"), started)
+            .context("The build didn't produce a WebAssembly file."),
+    }
+}
+
+/// Forwards the compiler diagnostics and returns the path of the WebAssembly
+/// file if the message announces one.
+fn handle_cargo_message(
+    message: &serde_json::Value,
+    on_message: &mut impl FnMut(String, LogLevel),
+) -> Option<PathBuf> {
+    match message["reason"].as_str()? {
+        "compiler-message" => {
+            let diagnostic = &message["message"];
+            let rendered = diagnostic["rendered"].as_str()?;
+            let level = match diagnostic["level"].as_str()? {
+                level if level.starts_with("error") => LogLevel::Error,
+                "warning" => LogLevel::Warning,
+                _ => LogLevel::Info,
+            };
+            on_message(rendered.trim_end().to_owned(), level);
+            None
+        }
+        "compiler-artifact" => message["filenames"]
+            .as_array()?
+            .iter()
+            .filter_map(|path| path.as_str())
+            .find(|path| path.ends_with(".wasm"))
+            .map(PathBuf::from),
+        _ => None,
+    }
+}
+
+/// Custom build commands don't report what they produced, so the most recently
+/// modified WebAssembly file in the target directory is used instead, as long
+/// as it was written during the build.
+fn newest_wasm_file(target_dir: &Path, since: SystemTime) -> Option<PathBuf> {
+    // The files are in `target/<profile>` or `target/<target>/<profile>`.
+    let mut dirs = vec![(target_dir.to_path_buf(), 0)];
+    let mut newest = None::<(SystemTime, PathBuf)>;
+    while let Some((dir, depth)) = dirs.pop() {
+        for entry in fs::read_dir(&dir).into_iter().flatten().flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                if depth < 2 {
+                    dirs.push((path, depth + 1));
+                }
+            } else if path.extension().is_some_and(|ext| ext == "wasm") {
+                let Ok(modified) = entry.metadata().and_then(|m| m.modified()) else {
+                    continue;
+                };
+                if modified >= since && newest.as_ref().is_none_or(|(time, _)| modified > *time) {
+                    newest = Some((modified, path));
+                }
+            }
+        }
+    }
+    newest.map(|(_, path)| path)
+}
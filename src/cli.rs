@@ -0,0 +1,94 @@
+use std::{path::PathBuf, sync::OnceLock, time::Duration};
+
+use clap::Parser;
+
+pub static ARGS: OnceLock<Args> = OnceLock::new();
+
+/// The IDE for LiveSplit One's auto splitting runtime.
+#[derive(Parser)]
+#[command(version, about)]
+pub struct Args {
+    /// The auto splitter to load on startup.
+    #[arg(value_name = "WASM")]
+    pub wasm_path: Option<PathBuf>,
+
+    /// Compile the auto splitter without optimizations.
+    #[arg(long)]
+    pub no_optimize: bool,
+
+    /// A JSON file or a LiveSplit splits or layout file with the settings map
+    /// to start the auto splitter with.
+    #[arg(long, value_name = "FILE", requires = "wasm_path")]
+    pub settings: Option<PathBuf>,
+
+    /// A LiveSplit splits file or a text file with one segment name per line
+    /// that determines the segments of the run.
+    #[arg(long, value_name = "FILE")]
+    pub splits: Option<PathBuf>,
+
+    /// The initial width of the window in logical pixels.
+    #[arg(long, default_value_t = 800.0)]
+    pub width: f64,
+
+    /// The initial height of the window in logical pixels.
+    #[arg(long, default_value_t = 600.0)]
+    pub height: f64,
+
+    /// Start with the window maximized.
+    #[arg(long)]
+    pub maximized: bool,
+
+    /// The command that builds auto splitters that are opened as a Cargo
+    /// project. It is split at whitespace.
+    #[arg(long, value_name = "COMMAND")]
+    pub build_command: Option<String>,
+
+    /// The command that launches the debugger in a terminal. `{script}` is
+    /// replaced with the path to the generated LLDB script and `{pid}` with
+    /// the process ID of the IDE.
+    #[arg(long, value_name = "COMMAND", default_value = crate::debugger::DEFAULT_COMMAND)]
+    pub debugger: String,
+
+    /// The maximum number of log entries that are kept in the IDE. The oldest
+    /// entries are dropped first.
+    #[arg(long, value_name = "ENTRIES", default_value_t = 10_000)]
+    pub log_capacity: usize,
+
+    /// Continuously append all the logs to a rotating file in this directory.
+    /// The directory defaults to the local data directory.
+    #[arg(long, value_name = "DIR")]
+    pub session_log: Option<Option<PathBuf>>,
+
+    /// Interrupt the auto splitter when a single tick takes longer than this
+    /// many seconds. A value of 0 disables the watchdog.
+    #[arg(long, value_name = "SECONDS", default_value = "5", value_parser = parse_watchdog_limit)]
+    // Fully qualified, so clap doesn't treat the argument as optional.
+    pub watchdog: std::option::Option<Duration>,
+
+    /// Run the auto splitter without opening a window and print its logs,
+    /// timer events and variables to stdout.
+    #[arg(long, requires = "wasm_path")]
+    pub headless: bool,
+
+    /// Stop the headless runner after this many seconds.
+    #[arg(long, value_name = "SECONDS", requires = "headless", value_parser = parse_duration)]
+    pub duration: Option<Duration>,
+}
+
+fn parse_watchdog_limit(value: &str) -> Result<Option<Duration>, String> {
+    let secs = value.parse::<f64>().map_err(|e| e.to_string())?;
+    crate::watchdog::limit_from_secs(secs)
+}
+
+fn parse_duration(value: &str) -> Result<Duration, String> {
+    let secs = value.parse::<f64>().map_err(|e| e.to_string())?;
+    if secs <= 0.0 {
+        return Err(format!("{value} is not a positive number of seconds."));
+    }
+    Duration::try_from_secs_f64(secs)
+        .map_err(|_| format!("{value} is not a valid duration in seconds."))
+}
+
+pub fn args() -> &'static Args {
+    ARGS.get().unwrap()
+}
@@ -0,0 +1,61 @@
+//! Reads the DWARF debug information that auto splitters built in debug mode
+//! contain as custom sections.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::PathBuf,
+};
+
+use anyhow::{Context, Result};
+use gimli::{Dwarf, EndianSlice, LittleEndian, SectionId};
+use wasmparser::{Parser, Payload};
+
+/// Lists all the source files that the auto splitter got compiled from. This is
+/// empty if the auto splitter has no debug information.
+pub fn source_files(wasm: &[u8]) -> Result<Vec<PathBuf>> {
+    let mut sections = HashMap::new();
+    for payload in Parser::new(0).parse_all(wasm) {
+        if let Payload::CustomSection(reader) = payload.context("Failed parsing the module.")? {
+            if reader.name().starts_with(".debug_") {
+                sections.insert(reader.name(), reader.data());
+            }
+        }
+    }
+    if sections.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let dwarf = Dwarf::load(|id: SectionId| -> Result<_, gimli::Error> {
+        let data = sections.get(id.name()).copied().unwrap_or_default();
+        Ok(EndianSlice::new(data, LittleEndian))
+    })?;
+
+    let mut files = BTreeSet::new();
+    let mut units = dwarf.units();
+    while let Some(header) = units.next()? {
+        let unit = dwarf.unit(header)?;
+        let Some(program) = &unit.line_program else {
+            continue;
+        };
+        let header = program.header();
+        for file in header.file_names() {
+            // Absolute paths replace what has been pushed so far, so this
+            // resolves the path the same way the compiler did.
+            let mut path = PathBuf::new();
+            if let Some(comp_dir) = &unit.comp_dir {
+                path.push(&*comp_dir.to_string_lossy());
+            }
+            if let Some(directory) = file.directory(header) {
+                path.push(&*dwarf.attr_string(&unit, directory)?.to_string_lossy());
+            }
+            path.push(
+                &*dwarf
+                    .attr_string(&unit, file.path_name())?
+                    .to_string_lossy(),
+            );
+            files.insert(path);
+        }
+    }
+
+    Ok(files.into_iter().collect())
+}
@@ -0,0 +1,131 @@
+//! Attaches a debugger to the IDE, so the auto splitter's code can be stepped
+//! through. The runtime registers the JIT compiled code of the auto splitter
+//! with the debugger through the GDB JIT interface, which LLDB needs to be told
+//! to use.
+
+use std::{
+    env, fs,
+    path::PathBuf,
+    process::{self, Command},
+};
+
+use anyhow::{bail, Context, Result};
+
+pub const DEFAULT_COMMAND: &str = "lldb -s {script}";
+
+/// Writes the command script and launches the debugger in a new terminal
+/// window. The `{script}` and `{pid}` placeholders in the command get replaced
+/// with the path to the script and the IDE's process ID. Each breakpoint is
+/// either a function name or a `file:line` location.
+pub fn launch(command: &str, breakpoints: &[&str]) -> Result<PathBuf> {
+    let script_path = env::temp_dir().join(format!("auto-splitting-ide-{}.lldb", process::id()));
+    fs::write(&script_path, script(process::id(), breakpoints))
+        .context("Failed writing the debugger script.")?;
+
+    // The command is split before the placeholders are replaced, so paths
+    // with spaces stay intact.
+    let args = command
+        .split_whitespace()
+        .map(|arg| {
+            arg.replace("{script}", &script_path.to_string_lossy())
+                .replace("{pid}", &process::id().to_string())
+        })
+        .collect::<Vec<_>>();
+    if args.is_empty() {
+        bail!("The debugger command is empty.");
+    }
+
+    open_in_terminal(&args).context("Failed launching the debugger.")?;
+
+    Ok(script_path)
+}
+
+fn script(pid: u32, breakpoints: &[&str]) -> String {
+    let mut script = String::from(
+        "# Generated by the Auto Splitting IDE.\n\
+        # Loads the debug information of JIT compiled code, like the auto splitter.\n\
+        settings set plugin.jit-loader.gdb.enable on\n",
+    );
+    script.push_str(&format!("process attach --pid {pid}\n"));
+    for breakpoint in breakpoints {
+        match breakpoint.rsplit_once(':') {
+            Some((file, line)) if line.parse::<u32>().is_ok() => {
+                script.push_str(&format!("breakpoint set --file {file} --line {line}\n"));
+            }
+            _ => script.push_str(&format!("breakpoint set --name {breakpoint}\n")),
+        }
+    }
+    script.push_str("continue\n");
+    script
+}
+
+#[cfg(windows)]
+fn open_in_terminal(args: &[String]) -> Result<()> {
+    Command::new("cmd")
+        // The first quoted argument of `start` is the title of the window.
+        .args(["/C", "start", ""])
+        .args(args)
+        .spawn()?;
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn open_in_terminal(args: &[String]) -> Result<()> {
+    let command = args.join(" ").replace('\\', "\\\\").replace('"', "\\\"");
+    Command::new("osascript")
+        .arg("-e")
+        .arg(format!(
+            "tell application \"Terminal\" to do script \"{command}\""
+        ))
+        .spawn()?;
+    Ok(())
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn open_in_terminal(args: &[String]) -> Result<()> {
+    // The terminals differ in how they take the command to run.
+    let mut terminals = vec![
+        ("x-terminal-emulator", "-e"),
+        ("gnome-terminal", "--"),
+        ("konsole", "-e"),
+        ("xfce4-terminal", "-x"),
+        ("alacritty", "-e"),
+        ("kitty", "--"),
+        ("xterm", "-e"),
+    ];
+    let preferred = env::var("TERMINAL").ok();
+    if let Some(preferred) = &preferred {
+        terminals.insert(0, (preferred, "-e"));
+    }
+
+    for (terminal, separator) in terminals {
+        match Command::new(terminal).arg(separator).args(args).spawn() {
+            Ok(_) => return Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    bail!("No terminal emulator found. Set the TERMINAL environment variable to one.");
+}
+
+/// Most distributions only allow debuggers to attach to their own child
+/// processes, which the IDE isn't. This returns a warning explaining how to
+/// allow it if that's the case.
+#[cfg(target_os = "linux")]
+pub fn ptrace_warning() -> Option<String> {
+    let scope = fs::read_to_string("/proc/sys/kernel/yama/ptrace_scope").ok()?;
+    let scope = scope.trim().parse::<u32>().ok()?;
+    (scope > 0).then(|| {
+        format!(
+            "The debugger may not be allowed to attach to the IDE, because \
+            /proc/sys/kernel/yama/ptrace_scope is {scope}. Set it to 0 with \
+            `echo 0 | sudo tee /proc/sys/kernel/yama/ptrace_scope` or run the \
+            debugger as root."
+        )
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn ptrace_warning() -> Option<String> {
+    None
+}
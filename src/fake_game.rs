@@ -0,0 +1,85 @@
+//! Launches the fake game that is built alongside the IDE and sends it the
+//! commands that change its state.
+
+use std::{
+    env,
+    io::{BufRead, BufReader, Write},
+    path::PathBuf,
+    process::{Child, ChildStdin, Command, Stdio},
+    thread,
+};
+
+use anyhow::{bail, Context, Result};
+use livesplit_auto_splitting::LogLevel;
+
+pub struct FakeGame {
+    child: Child,
+    stdin: ChildStdin,
+}
+
+impl FakeGame {
+    /// Starts the fake game. Everything it prints is passed to `on_output`.
+    pub fn launch(
+        on_output: impl FnMut(String, LogLevel) + Clone + Send + 'static,
+    ) -> Result<Self> {
+        let path = path()?;
+        let mut child = Command::new(&path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Failed launching the fake game at {}.", path.display()))?;
+
+        let stdin = child.stdin.take().unwrap();
+        forward_lines(
+            child.stdout.take().unwrap(),
+            LogLevel::Info,
+            on_output.clone(),
+        );
+        forward_lines(child.stderr.take().unwrap(), LogLevel::Warning, on_output);
+
+        Ok(Self { child, stdin })
+    }
+
+    pub fn pid(&self) -> u32 {
+        self.child.id()
+    }
+
+    pub fn send(&mut self, command: &str) -> Result<()> {
+        writeln!(self.stdin, "{command}").context("Failed sending the command to the fake game.")
+    }
+}
+
+impl Drop for FakeGame {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// The fake game is built into the same directory as the IDE. `cargo run`
+/// only builds the IDE though, so it may be missing.
+fn path() -> Result<PathBuf> {
+    let ide = env::current_exe().context("Failed locating the IDE.")?;
+    let path = ide.with_file_name(format!("fake-game{}", env::consts::EXE_SUFFIX));
+    if !path.is_file() {
+        bail!(
+            "The fake game is missing. It is expected at {}. Build it with \
+            `cargo build --bin fake-game` using the same profile as the IDE.",
+            path.display(),
+        );
+    }
+    Ok(path)
+}
+
+fn forward_lines(
+    output: impl std::io::Read + Send + 'static,
+    level: LogLevel,
+    mut on_output: impl FnMut(String, LogLevel) + Send + 'static,
+) {
+    thread::spawn(move || {
+        for line in BufReader::new(output).lines().map_while(Result::ok) {
+            on_output(line, level);
+        }
+    });
+}
@@ -0,0 +1,126 @@
+//! Watches a directory and reacts to its changes once they settled down.
+
+use std::{
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use anyhow::{Context, Result};
+use livesplit_auto_splitting::LogLevel;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+
+/// Writing files often causes multiple events in quick succession, so the
+/// changes are only reacted to once nothing changed for this long.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Whether the watcher that reacts to the changes got dropped. Reacting to a
+/// change may take a while, so its results should be thrown away then.
+#[derive(Clone)]
+pub struct Cancellation(Arc<AtomicBool>);
+
+impl Cancellation {
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+}
+
+/// Watches a directory and calls back on a separate thread whenever it
+/// changed. Dropping it stops watching.
+pub struct DebouncedWatcher {
+    _watcher: RecommendedWatcher,
+    trigger: mpsc::Sender<notify::Result<()>>,
+    cancellation: Cancellation,
+}
+
+impl DebouncedWatcher {
+    /// Calls `on_change` once the events that `is_relevant` accepts stopped
+    /// coming in. Errors of the watcher are passed to `on_error`.
+    pub fn new(
+        dir: &Path,
+        mode: RecursiveMode,
+        is_relevant: impl Fn(&notify::Event) -> bool + Send + 'static,
+        mut on_change: impl FnMut(&Cancellation) + Send + 'static,
+        mut on_error: impl FnMut(notify::Error) + Send + 'static,
+    ) -> Result<Self> {
+        let (trigger, triggered) = mpsc::channel();
+
+        let mut watcher = notify::recommended_watcher({
+            let trigger = trigger.clone();
+            move |event: notify::Result<notify::Event>| match event {
+                Ok(event) => {
+                    if is_relevant(&event) {
+                        let _ = trigger.send(Ok(()));
+                    }
+                }
+                Err(e) => {
+                    let _ = trigger.send(Err(e));
+                }
+            }
+        })
+        .context("Failed creating the file watcher.")?;
+        watcher
+            .watch(dir, mode)
+            .with_context(|| format!("Failed watching `{}`.", dir.display()))?;
+
+        let cancellation = Cancellation(Arc::new(AtomicBool::new(false)));
+
+        thread::spawn({
+            let cancellation = cancellation.clone();
+            move || {
+                // The thread stops once the watcher is dropped, as that closes
+                // the channel.
+                while let Ok(event) = triggered.recv() {
+                    if cancellation.is_cancelled() {
+                        break;
+                    }
+                    if let Err(e) = event {
+                        on_error(e);
+                        continue;
+                    }
+                    while triggered.recv_timeout(DEBOUNCE).is_ok() {}
+                    if cancellation.is_cancelled() {
+                        break;
+                    }
+                    on_change(&cancellation);
+                }
+            }
+        });
+
+        Ok(Self {
+            _watcher: watcher,
+            trigger,
+            cancellation,
+        })
+    }
+
+    /// Calls `on_change` as if the directory changed.
+    pub fn trigger(&self) {
+        let _ = self.trigger.send(Ok(()));
+    }
+}
+
+impl Drop for DebouncedWatcher {
+    fn drop(&mut self) {
+        self.cancellation.0.store(true, Ordering::Release);
+    }
+}
+
+/// Reports the errors of the file watcher as warnings, as it keeps watching.
+pub fn log_errors(
+    mut on_message: impl FnMut(String, LogLevel) + Send + 'static,
+) -> impl FnMut(notify::Error) + Send + 'static {
+    move |e| {
+        on_message(
+            format!(
+                "{:?}",
+                anyhow::Error::new(e).context("The file watcher failed.")
+            ),
+            LogLevel::Warning,
+        )
+    }
+}
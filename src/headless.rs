@@ -0,0 +1,200 @@
+use std::{fmt, process::ExitCode, sync::atomic::AtomicBool, thread, time::Instant};
+
+use anyhow::Context;
+use livesplit_auto_splitting::{LogLevel, Timer, TimerState};
+
+use crate::{
+    build_runtime,
+    cli::Args,
+    compile_file, level_name,
+    runtime_thread::{self, TickInfo},
+    settings_file, splits,
+    watchdog::{self, WATCHDOG},
+    TimerEvent, TimerModel, UTC_OFFSET,
+};
+
+/// A timer that prints everything the auto splitter does to stdout instead of
+/// showing it in the UI.
+struct HeadlessTimer {
+    model: TimerModel,
+}
+
+impl HeadlessTimer {
+    fn print_event(&self, event: Option<TimerEvent>) {
+        if let Some(event) = event {
+            print("EVENT", event);
+        }
+    }
+}
+
+impl Timer for HeadlessTimer {
+    fn state(&self) -> TimerState {
+        self.model.state
+    }
+
+    fn start(&mut self) {
+        let event = self.model.start();
+        self.print_event(event);
+    }
+
+    fn split(&mut self) {
+        let event = self.model.split();
+        self.print_event(event);
+    }
+
+    fn skip_split(&mut self) {
+        let event = self.model.skip_split();
+        self.print_event(event);
+    }
+
+    fn undo_split(&mut self) {
+        let event = self.model.undo_split();
+        self.print_event(event);
+    }
+
+    fn reset(&mut self) {
+        let event = self.model.reset();
+        self.print_event(event);
+    }
+
+    fn set_game_time(&mut self, time: time::Duration) {
+        self.model.set_game_time(time);
+    }
+
+    fn pause_game_time(&mut self) {
+        if self.model.pause_game_time() {
+            print("EVENT", "Game time paused.");
+        }
+    }
+
+    fn resume_game_time(&mut self) {
+        if self.model.resume_game_time() {
+            print("EVENT", "Game time resumed.");
+        }
+    }
+
+    fn set_variable(&mut self, key: &str, value: &str) {
+        if self.model.set_variable(key, value) {
+            print("VAR", format_args!("{key} = {value}"));
+        }
+    }
+
+    fn log_auto_splitter(&mut self, message: fmt::Arguments<'_>) {
+        print("LOG", message);
+    }
+
+    fn log_runtime(&mut self, message: fmt::Arguments<'_>, log_level: LogLevel) {
+        print(&level_name(log_level).to_uppercase(), message);
+    }
+
+    fn current_split_index(&self) -> Option<usize> {
+        self.model.current_split_index()
+    }
+
+    fn segment_splitted(&self, index: usize) -> Option<bool> {
+        self.model.segment_splitted(index)
+    }
+}
+
+fn print(kind: &str, message: impl fmt::Display) {
+    let now = time::OffsetDateTime::now_utc().to_offset(*UTC_OFFSET.get().unwrap());
+    let (h, m, s) = now.to_hms();
+    println!("{h:02}:{m:02}:{s:02} {kind:<7} {message}");
+}
+
+/// Runs the auto splitter without opening a window until it gets killed or
+/// the duration passed via `--duration` elapsed.
+pub fn run(args: &Args) -> ExitCode {
+    let Some(wasm_path) = &args.wasm_path else {
+        print(
+            "ERROR",
+            "The headless mode requires an auto splitter to run.",
+        );
+        return ExitCode::FAILURE;
+    };
+
+    let settings_map = match args.settings.as_deref().map(settings_file::load) {
+        Some(Ok(settings_map)) => Some(settings_map),
+        Some(Err(e)) => {
+            print("ERROR", format_args!("{e:?}"));
+            return ExitCode::FAILURE;
+        }
+        None => None,
+    };
+
+    let mut model = TimerModel::new();
+    if let Some(splits) = &args.splits {
+        match splits::load(splits) {
+            Ok(segments) => model.segments = segments,
+            Err(e) => {
+                print("ERROR", format_args!("{e:?}"));
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let runtime = build_runtime(!args.no_optimize);
+    let timer = HeadlessTimer { model };
+    let auto_splitter = match compile_file(&runtime, wasm_path).and_then(|module| {
+        module
+            .instantiate(timer, settings_map, None)
+            .context("Failed starting the auto splitter.")
+    }) {
+        Ok(auto_splitter) => auto_splitter,
+        Err(e) => {
+            print("ERROR", format_args!("{e:?}"));
+            return ExitCode::FAILURE;
+        }
+    };
+    print("INFO", "Auto splitter loaded.");
+
+    let deadline = args
+        .duration
+        // A deadline too far in the future to represent is as good as none.
+        .and_then(|duration| Instant::now().checked_add(duration));
+    let running = AtomicBool::new(true);
+    let mut processes = Vec::new();
+    let mut tick_number = 0;
+
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            watchdog::run(&WATCHDOG, &running, |message| {
+                print("ERROR", message);
+            })
+        });
+
+        runtime_thread::run_loop(&running, || {
+            tick_number += 1;
+            let TickInfo {
+                result,
+                processes: new_processes,
+                ..
+            } = runtime_thread::tick(&auto_splitter, tick_number);
+
+            if let Err(e) = result {
+                print(
+                    "ERROR",
+                    format_args!("{:?}", e.context("Failed executing the auto splitter.")),
+                );
+            }
+
+            if new_processes != processes {
+                for (pid, path) in new_processes.iter().filter(|p| !processes.contains(p)) {
+                    print("ATTACH", format_args!("{pid} {path}"));
+                }
+                for (pid, path) in processes.iter().filter(|p| !new_processes.contains(p)) {
+                    print("DETACH", format_args!("{pid} {path}"));
+                }
+                processes = new_processes;
+            }
+
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                running.store(false, std::sync::atomic::Ordering::Relaxed);
+            }
+
+            auto_splitter.tick_rate()
+        });
+    });
+
+    ExitCode::SUCCESS
+}
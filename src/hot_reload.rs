@@ -0,0 +1,144 @@
+//! Watches the auto splitter's file and reloads it once it has been completely
+//! written.
+
+use std::{
+    fs,
+    hash::{DefaultHasher, Hash, Hasher},
+    io,
+    path::PathBuf,
+};
+
+use anyhow::{Context, Result};
+use livesplit_auto_splitting::LogLevel;
+use notify::{EventKind, RecursiveMode};
+use wasmparser::Parser;
+
+use crate::file_watcher::{self, DebouncedWatcher};
+
+/// Watches the file of the auto splitter. Dropping it stops watching.
+pub struct WasmWatcher {
+    _watcher: DebouncedWatcher,
+}
+
+impl WasmWatcher {
+    /// Calls `on_change` whenever the file changed to a different, complete
+    /// WebAssembly module. Anything worth knowing about is passed to
+    /// `on_message`.
+    pub fn new(
+        path: PathBuf,
+        mut on_change: impl FnMut() + Send + 'static,
+        on_message: impl FnMut(String, LogLevel) + Clone + Send + 'static,
+    ) -> Result<Self> {
+        // Many tools write the file to a temporary location and rename it
+        // afterwards, or delete and recreate it. Watching the file itself
+        // would lose track of it then, so the directory is watched instead.
+        let dir = path
+            .parent()
+            .context("The auto splitter is not in a directory.")?
+            .to_path_buf();
+        let file_name = path.file_name().map(|name| name.to_os_string());
+
+        let mut last_hash = fs::read(&path).ok().map(|data| hash(&data));
+
+        let watcher = DebouncedWatcher::new(
+            &dir,
+            RecursiveMode::NonRecursive,
+            move |event| match event.kind {
+                EventKind::Create(_) | EventKind::Modify(_) => event
+                    .paths
+                    .iter()
+                    .any(|path| path.file_name() == file_name.as_deref()),
+                _ => event.need_rescan(),
+            },
+            {
+                let mut on_message = on_message.clone();
+                move |_| {
+                    let data = match fs::read(&path) {
+                        Ok(data) => data,
+                        // The file may be in the middle of getting recreated, in
+                        // which case there is another event once it exists again.
+                        Err(e) if e.kind() == io::ErrorKind::NotFound => return,
+                        Err(e) => {
+                            on_message(
+                                format!(
+                                    "{:?}",
+                                    anyhow::Error::new(e)
+                                        .context("Failed reading the auto splitter.")
+                                ),
+                                LogLevel::Warning,
+                            );
+                            return;
+                        }
+                    };
+
+                    let new_hash = hash(&data);
+                    if last_hash == Some(new_hash) {
+                        return;
+                    }
+
+                    if !is_complete_module(&data) {
+                        on_message(
+                            "The auto splitter changed, but it is not a complete WebAssembly \
+                            module. It gets reloaded once it changes again."
+                                .into(),
+                            LogLevel::Warning,
+                        );
+                        return;
+                    }
+
+                    last_hash = Some(new_hash);
+                    on_change();
+                }
+            },
+            file_watcher::log_errors(on_message),
+        )?;
+
+        Ok(Self { _watcher: watcher })
+    }
+}
+
+fn hash(data: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    data.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Files that are still being written are usually cut off somewhere, which the
+/// parser notices.
+fn is_complete_module(data: &[u8]) -> bool {
+    Parser::is_core_wasm(data)
+        && Parser::new(0)
+            .parse_all(data)
+            .all(|payload| payload.is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A module with a type section that declares a single `fn()`.
+    const MODULE: &[u8] = &[
+        0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00, 0x01, 0x04, 0x01, 0x60, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn accepts_complete_modules() {
+        assert!(is_complete_module(MODULE));
+        assert!(is_complete_module(&MODULE[..8]));
+    }
+
+    #[test]
+    fn rejects_modules_that_are_cut_off() {
+        for len in (0..MODULE.len()).filter(|&len| len != 8) {
+            assert!(!is_complete_module(&MODULE[..len]), "{len} bytes");
+        }
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        assert!(!is_complete_module(b"not a module"));
+        let mut component = MODULE.to_vec();
+        component[4..8].copy_from_slice(&[0x0D, 0x00, 0x01, 0x00]);
+        assert!(!is_complete_module(&component));
+    }
+}
@@ -3,11 +3,13 @@
 use std::{
     borrow::Cow,
     path::{Path, PathBuf},
+    process::ExitCode,
     rc::Rc,
     sync::{Arc, OnceLock},
     thread,
 };
 
+use clap::Parser;
 use dioxus::{
     desktop::{
         tao::window::WindowSizeConstraints, wry::dpi::PixelUnit, Config, LogicalSize, WindowBuilder,
@@ -15,31 +17,57 @@ use dioxus::{
     prelude::*,
 };
 use dioxus_material_icons::MaterialIconStylesheet;
-use indexmap::IndexMap;
 use livesplit_auto_splitting::{
     settings::{self, Widget},
-    time, TimerState,
+    LogLevel,
 };
 use time::UtcOffset;
 
+mod build;
+mod cli;
+mod debug_info;
+mod debugger;
+mod fake_game;
+mod file_watcher;
+mod headless;
 mod hooks;
+mod hot_reload;
+mod module_info;
 mod runtime_thread;
+mod session_log;
+mod settings_file;
+mod splits;
 mod timer;
+mod trace;
 mod ui;
+mod watchdog;
 
 use hooks::use_transparency;
 use timer::*;
+use trace::TickTrace;
 use ui::*;
+use watchdog::WATCHDOG;
 
 static UTC_OFFSET: OnceLock<time::UtcOffset> = OnceLock::new();
 
 struct Widgets(Arc<Vec<Widget>>);
 
-fn main() {
+fn main() -> ExitCode {
     UTC_OFFSET
         .set(UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC))
         .unwrap();
 
+    #[cfg(windows)]
+    attach_console();
+
+    let args = cli::ARGS.get_or_init(cli::Args::parse);
+
+    WATCHDOG.set_limit(args.watchdog);
+
+    if args.headless {
+        return headless::run(args);
+    }
+
     dioxus::LaunchBuilder::desktop()
         .with_cfg(
             Config::new().with_menu(None).with_window(
@@ -51,53 +79,142 @@ fn main() {
                         None,
                         None,
                     ))
-                    .with_inner_size(LogicalSize::new(800, 600)),
+                    .with_inner_size(LogicalSize::new(args.width, args.height))
+                    .with_maximized(args.maximized),
             ),
         )
         .launch(App);
+
+    ExitCode::SUCCESS
+}
+
+/// Release builds use the Windows GUI subsystem, so they don't get a console
+/// by default. Attaching to the console of the parent process lets `--help`
+/// and the headless mode print to the terminal that launched the IDE.
+#[cfg(windows)]
+fn attach_console() {
+    use windows_sys::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+/// The name of the log level, as it is shown in the logs.
+fn level_name(level: LogLevel) -> &'static str {
+    match level {
+        LogLevel::Trace => "Trace",
+        LogLevel::Debug => "Debug",
+        LogLevel::Info => "Info",
+        LogLevel::Warning => "Warning",
+        LogLevel::Error => "Error",
+    }
 }
 
 #[component]
 fn App() -> Element {
     let is_transparent = use_transparency();
     let wasm_path = use_signal_sync(|| None::<PathBuf>);
-    let logs = use_signal_sync(LogEntries::new);
-    let timer_state = use_signal_sync(|| TimerState::NotRunning);
-    let split_index = use_signal_sync(|| 0);
-    let segment_splitted = use_signal_sync(Vec::new);
-    let game_time = use_signal_sync(|| time::Duration::ZERO);
-    let game_time_state = use_signal_sync(|| GameTimeState::NotInitialized);
-    let variables = use_signal_sync(IndexMap::new);
+    let logs = use_signal_sync(|| {
+        let mut logs = LogEntries::new(cli::args().log_capacity);
+        if let Some(dir) = &cli::args().session_log {
+            logs.start_session_log(dir.as_deref());
+        }
+        logs
+    });
+    let timer_events = use_signal_sync(TimerEvents::new);
+    let timer_event_batch = use_signal_sync(Vec::new);
+    let log_batch = use_signal_sync(Vec::new);
+    let timer_model = use_signal_sync(TimerModel::new);
+    let variable_history = use_signal_sync(VariableHistory::new);
+    let source_files = use_signal_sync(Vec::new);
+    let module_info = use_signal_sync(|| None);
+    let load_options = use_signal_sync(LoadOptions::default);
+    let memory_inspector = use_signal_sync(MemoryInspector::default);
     let processes = use_signal_sync(Vec::new);
     let settings_widgets = use_signal_sync(|| Widgets(Arc::new(Vec::new())));
     let settings_map = use_signal_sync(settings::Map::new);
+    let settings_restored = use_signal_sync(|| false);
     let statistics = use_signal_sync(StatisticsData::default);
+    let trace = use_signal_sync(TickTrace::new);
     let timer = use_signal_sync(|| IdeTimer {
-        split_index,
-        segment_splitted,
-        timer_state,
-        game_time,
-        game_time_state,
-        variables,
+        model: timer_model,
+        variable_history,
         processes,
         settings_widgets,
         settings_map,
+        settings_restored,
         logs,
+        log_batch,
+        timer_events,
+        timer_event_batch,
         wasm_path,
+        source_files,
+        module_info,
+        load_options,
+        memory_inspector,
         statistics,
+        trace,
+        source: TimerEventSource::Manual,
     });
-    // TODO: CLI Args
-    let optimize = use_signal(|| true);
+    let optimize = use_signal(|| !cli::args().no_optimize);
     let runtime = use_signal_sync(|| build_runtime(optimize()));
     let module = use_signal_sync(|| None);
     let auto_splitter = use_signal_sync(|| None);
 
+    use_hook(move || {
+        let args = cli::args();
+        if let Some(splits) = &args.splits {
+            match splits::load(splits) {
+                Ok(segments) => { timer_model }.write().segments = segments,
+                Err(e) => {
+                    { logs }
+                        .write()
+                        .push_level(format!("{e:?}"), LogLevel::Error);
+                }
+            }
+        }
+        let Some(path) = &args.wasm_path else {
+            return;
+        };
+        let settings_map = args.settings.as_deref().and_then(|settings| {
+            settings_file::load(settings)
+                .map_err(|e| {
+                    { logs }
+                        .write()
+                        .push_level(format!("{e:?}"), LogLevel::Error);
+                })
+                .ok()
+        });
+        let timer = *timer.read();
+        timer.load_file(path, settings_map, runtime, module, auto_splitter);
+    });
+
+    use_effect(move || {
+        let settings_map = settings_map.read();
+        if auto_splitter.peek().is_none() {
+            return;
+        }
+        if let Some(path) = &*wasm_path.peek() {
+            if let Err(e) = settings_file::store_saved(path, &settings_map) {
+                { logs }.write().push_level(
+                    format!("{:?}", e.context("Failed saving the settings.")),
+                    LogLevel::Warning,
+                );
+            }
+        }
+    });
+
     use_hook(move || {
         let thread = thread::spawn(move || {
             runtime_thread::run(auto_splitter, timer);
         });
+        let watchdog_thread = thread::spawn(move || {
+            watchdog::run(&WATCHDOG, &runtime_thread::RUNNING, |message| {
+                { logs }.write().push_level(message, LogLevel::Error);
+            });
+        });
         struct ThreadJoiner(
-            Option<thread::JoinHandle<()>>,
+            Vec<thread::JoinHandle<()>>,
             SyncSignal<Option<livesplit_auto_splitting::AutoSplitter<IdeTimer>>>,
         );
         impl Drop for ThreadJoiner {
@@ -105,12 +222,13 @@ fn App() -> Element {
                 if let Some(auto_splitter) = &*self.1.read() {
                     auto_splitter.interrupt_handle().interrupt();
                 }
-                let join_handle = self.0.take().unwrap();
                 runtime_thread::RUNNING.store(false, std::sync::atomic::Ordering::Relaxed);
-                let _ = join_handle.join();
+                for join_handle in self.0.drain(..) {
+                    let _ = join_handle.join();
+                }
             }
         }
-        Rc::new(ThreadJoiner(Some(thread), auto_splitter))
+        Rc::new(ThreadJoiner(vec![thread, watchdog_thread], auto_splitter))
     });
 
     let mut left_divider = use_signal(|| DividerState {
@@ -160,6 +278,20 @@ fn App() -> Element {
 
         div {
             class: "app",
+            // Makes the window focusable, so the timer shortcuts work without
+            // having to click into the window first.
+            tabindex: -1,
+            onmounted: move |ev| async move {
+                let _ = ev.set_focus(true).await;
+            },
+            onkeydown: move |ev| {
+                if !ev.modifiers().intersects(Modifiers::CONTROL | Modifiers::ALT | Modifiers::META)
+                    && !ev.is_auto_repeating()
+                    && ui::handle_timer_shortcut(timer, &ev.code())
+                {
+                    ev.prevent_default();
+                }
+            },
             onmousemove: move |ev| {
                 for divider in &mut [
                     &mut left_divider,
@@ -202,26 +334,36 @@ fn App() -> Element {
                 gap: "10px",
                 LeftSideBar {
                     timer,
-                    split_index,
-                    timer_state,
-                    game_time,
-                    game_time_state,
+                    timer_model,
                     runtime,
                     module,
                     auto_splitter,
                     statistics,
+                    trace,
+                    logs,
+                    module_info,
                     optimize,
                     width: left_divider.read().size,
                 }
                 VerticalDivider { state: left_divider }
-                CentralPanel { logs, bottom_divider }
+                CentralPanel {
+                    logs,
+                    timer_events,
+                    source_files,
+                    memory_inspector,
+                    bottom_divider,
+                }
                 VerticalDivider { state: right_divider }
                 RightSideBar {
                     auto_splitter,
-                    variables,
+                    timer_model,
+                    variable_history,
                     processes,
                     settings_widgets,
                     settings_map,
+                    settings_restored,
+                    wasm_path,
+                    logs,
                     width: right_divider.read().size,
                 }
             }
@@ -0,0 +1,379 @@
+//! Describes what a WebAssembly module contains, so it's visible what the auto
+//! splitter expects from the runtime before it gets instantiated.
+
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use wasmparser::{ExternalKind, Parser, Payload, TypeRef};
+
+/// The functions that the runtime provides in the `env` module, grouped by the
+/// host API they belong to. This has to be kept in sync with the runtime, so
+/// other imports are only flagged as suspicious until instantiating the auto
+/// splitter confirms that they are missing.
+const HOST_APIS: &[(&str, &[&str])] = &[
+    (
+        "timer",
+        &[
+            "timer_get_state",
+            "timer_start",
+            "timer_split",
+            "timer_skip_split",
+            "timer_undo_split",
+            "timer_reset",
+            "timer_current_split_index",
+            "timer_segment_splitted",
+            "timer_set_variable",
+            "timer_set_game_time",
+            "timer_pause_game_time",
+            "timer_resume_game_time",
+        ],
+    ),
+    (
+        "process",
+        &[
+            "process_attach",
+            "process_attach_by_pid",
+            "process_detach",
+            "process_list_by_name",
+            "process_is_open",
+            "process_read",
+            "process_get_path",
+            "process_get_module_address",
+            "process_get_module_size",
+            "process_get_module_path",
+            "process_get_memory_range_count",
+            "process_get_memory_range_address",
+            "process_get_memory_range_size",
+            "process_get_memory_range_flags",
+        ],
+    ),
+    (
+        "runtime",
+        &[
+            "runtime_set_tick_rate",
+            "runtime_print_message",
+            "runtime_get_os",
+            "runtime_get_arch",
+        ],
+    ),
+    (
+        "user_settings",
+        &[
+            "user_settings_add_bool",
+            "user_settings_add_title",
+            "user_settings_add_choice",
+            "user_settings_add_choice_option",
+            "user_settings_add_file_select",
+            "user_settings_add_file_select_name_filter",
+            "user_settings_add_file_select_mime_filter",
+            "user_settings_set_tooltip",
+        ],
+    ),
+    (
+        "settings_map",
+        &[
+            "settings_map_new",
+            "settings_map_free",
+            "settings_map_load",
+            "settings_map_store",
+            "settings_map_store_if_unchanged",
+            "settings_map_copy",
+            "settings_map_insert",
+            "settings_map_get",
+            "settings_map_len",
+            "settings_map_get_key_by_index",
+            "settings_map_get_value_by_index",
+        ],
+    ),
+    (
+        "settings_list",
+        &[
+            "settings_list_new",
+            "settings_list_free",
+            "settings_list_copy",
+            "settings_list_len",
+            "settings_list_get",
+            "settings_list_push",
+            "settings_list_insert",
+        ],
+    ),
+    (
+        "setting_value",
+        &[
+            "setting_value_new_map",
+            "setting_value_new_list",
+            "setting_value_new_bool",
+            "setting_value_new_i64",
+            "setting_value_new_f64",
+            "setting_value_new_string",
+            "setting_value_free",
+            "setting_value_copy",
+            "setting_value_get_type",
+            "setting_value_get_map",
+            "setting_value_get_list",
+            "setting_value_get_bool",
+            "setting_value_get_i64",
+            "setting_value_get_f64",
+            "setting_value_get_string",
+        ],
+    ),
+];
+
+/// The runtime links the entire WASI preview 1 API.
+const WASI_MODULE: &str = "wasi_snapshot_preview1";
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum ImportStatus {
+    /// The runtime provides the import.
+    Expected,
+    /// The import is not one of the functions that the runtime provides, so
+    /// it is most likely missing.
+    Suspicious,
+    /// The runtime reported the import as missing when instantiating the
+    /// auto splitter.
+    Missing,
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Import {
+    pub module: String,
+    pub name: String,
+    pub kind: &'static str,
+    pub status: ImportStatus,
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Export {
+    pub name: String,
+    pub kind: &'static str,
+}
+
+#[derive(Clone, PartialEq)]
+pub struct CustomSection {
+    pub name: String,
+    pub size: usize,
+}
+
+#[derive(Clone, PartialEq, Default)]
+pub struct ModuleInfo {
+    /// The imports grouped by the host API they belong to.
+    pub imports: BTreeMap<String, Vec<Import>>,
+    pub exports: Vec<Export>,
+    pub custom_sections: Vec<CustomSection>,
+    pub total_size: usize,
+    pub code_size: usize,
+    pub data_size: usize,
+    pub has_dwarf: bool,
+}
+
+impl ModuleInfo {
+    pub fn parse(wasm: &[u8]) -> Result<Self> {
+        let mut info = Self {
+            total_size: wasm.len(),
+            ..Self::default()
+        };
+        for payload in Parser::new(0).parse_all(wasm) {
+            match payload.context("Failed parsing the module.")? {
+                Payload::ImportSection(reader) => {
+                    for import in reader {
+                        let import = import.context("Failed parsing an import.")?;
+                        let (namespace, is_provided) =
+                            host_api(import.module, import.name, import.ty);
+                        info.imports.entry(namespace).or_default().push(Import {
+                            module: import.module.into(),
+                            name: import.name.into(),
+                            kind: type_ref_kind(import.ty),
+                            status: if is_provided {
+                                ImportStatus::Expected
+                            } else {
+                                ImportStatus::Suspicious
+                            },
+                        });
+                    }
+                }
+                Payload::ExportSection(reader) => {
+                    for export in reader {
+                        let export = export.context("Failed parsing an export.")?;
+                        info.exports.push(Export {
+                            name: export.name.into(),
+                            kind: external_kind(export.kind),
+                        });
+                    }
+                }
+                Payload::CodeSectionStart { range, .. } => info.code_size = range.len(),
+                Payload::DataSection(reader) => info.data_size = reader.range().len(),
+                Payload::CustomSection(reader) => {
+                    info.has_dwarf |= reader.name().starts_with(".debug_");
+                    info.custom_sections.push(CustomSection {
+                        name: reader.name().into(),
+                        size: reader.data().len(),
+                    });
+                }
+                _ => {}
+            }
+        }
+        Ok(info)
+    }
+
+    /// The imports that are missing or most likely missing.
+    pub fn unsupported_imports(&self) -> impl Iterator<Item = &Import> {
+        self.imports
+            .values()
+            .flatten()
+            .filter(|import| import.status != ImportStatus::Expected)
+    }
+
+    /// Marks the imports that the runtime reported as missing in the error
+    /// of instantiating the auto splitter.
+    pub fn mark_missing_imports(&mut self, error: &anyhow::Error) {
+        for cause in error.chain() {
+            let cause = cause.to_string();
+            let Some((module, name)) = missing_import(&cause) else {
+                continue;
+            };
+            for import in self.imports.values_mut().flatten() {
+                if import.module == module && import.name == name {
+                    import.status = ImportStatus::Missing;
+                }
+            }
+        }
+    }
+}
+
+/// Wasmtime reports missing imports as ``unknown import: `module::name` has
+/// not been defined``.
+fn missing_import(message: &str) -> Option<(&str, &str)> {
+    let (_, rest) = message.split_once("unknown import: `")?;
+    let (import, _) = rest.split_once('`')?;
+    import.split_once("::")
+}
+
+/// Returns the host API that the import belongs to and whether the runtime
+/// provides it.
+fn host_api(module: &str, name: &str, ty: TypeRef) -> (String, bool) {
+    let is_func = matches!(ty, TypeRef::Func(_));
+    match module {
+        "env" => {
+            // Functions from a newer version of an API are still grouped with
+            // it, but flagged as suspicious.
+            if let Some((namespace, functions)) = HOST_APIS.iter().find(|(namespace, _)| {
+                name.strip_prefix(namespace)
+                    .is_some_and(|rest| rest.starts_with('_'))
+            }) {
+                ((*namespace).into(), is_func && functions.contains(&name))
+            } else {
+                (module.into(), false)
+            }
+        }
+        WASI_MODULE => (module.into(), is_func),
+        _ => (module.into(), false),
+    }
+}
+
+fn type_ref_kind(ty: TypeRef) -> &'static str {
+    match ty {
+        TypeRef::Func(_) => "function",
+        TypeRef::Table(_) => "table",
+        TypeRef::Memory(_) => "memory",
+        TypeRef::Global(_) => "global",
+        TypeRef::Tag(_) => "tag",
+    }
+}
+
+fn external_kind(kind: ExternalKind) -> &'static str {
+    match kind {
+        ExternalKind::Func => "function",
+        ExternalKind::Table => "table",
+        ExternalKind::Memory => "memory",
+        ExternalKind::Global => "global",
+        ExternalKind::Tag => "tag",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A module that imports a known function, a function that the runtime
+    /// doesn't know about, a function that isn't part of any host API, a WASI
+    /// function and a global that has the name of a known function.
+    fn module_info() -> ModuleInfo {
+        let imports: &[(&str, &str, &[u8])] = &[
+            ("env", "timer_start", &[0x00, 0x00]),
+            ("env", "timer_start_faster", &[0x00, 0x00]),
+            ("env", "print", &[0x00, 0x00]),
+            (WASI_MODULE, "fd_write", &[0x00, 0x00]),
+            ("env", "timer_get_state", &[0x03, 0x7F, 0x00]),
+        ];
+        let mut section = vec![imports.len() as u8];
+        for (module, name, ty) in imports {
+            section.push(module.len() as u8);
+            section.extend_from_slice(module.as_bytes());
+            section.push(name.len() as u8);
+            section.extend_from_slice(name.as_bytes());
+            section.extend_from_slice(ty);
+        }
+
+        let mut wasm = vec![0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00];
+        // A single `fn()` type for the functions to use.
+        wasm.extend_from_slice(&[0x01, 0x04, 0x01, 0x60, 0x00, 0x00]);
+        wasm.extend_from_slice(&[0x02, section.len() as u8]);
+        wasm.extend_from_slice(&section);
+        ModuleInfo::parse(&wasm).unwrap()
+    }
+
+    fn status(info: &ModuleInfo, name: &str, kind: &str) -> ImportStatus {
+        info.imports
+            .values()
+            .flatten()
+            .find(|import| import.name == name && import.kind == kind)
+            .unwrap()
+            .status
+    }
+
+    #[test]
+    fn flags_unknown_imports_before_instantiation() {
+        let info = module_info();
+        let statuses = [
+            ("timer_start", "function", ImportStatus::Expected),
+            ("timer_start_faster", "function", ImportStatus::Suspicious),
+            ("print", "function", ImportStatus::Suspicious),
+            ("fd_write", "function", ImportStatus::Expected),
+            ("timer_get_state", "global", ImportStatus::Suspicious),
+        ];
+        for (name, kind, expected) in statuses {
+            assert!(status(&info, name, kind) == expected, "{name}");
+        }
+        assert_eq!(info.imports["timer"].len(), 3);
+        assert_eq!(info.imports["env"].len(), 1);
+        assert_eq!(info.unsupported_imports().count(), 3);
+    }
+
+    #[test]
+    fn finds_the_missing_import_in_the_message() {
+        assert_eq!(
+            missing_import("unknown import: `env::timer_start_faster` has not been defined"),
+            Some(("env", "timer_start_faster")),
+        );
+        assert_eq!(missing_import("failed to parse WebAssembly module"), None);
+        assert_eq!(
+            missing_import("unknown import: `env` has not been defined"),
+            None
+        );
+    }
+
+    #[test]
+    fn marks_the_imports_that_failed_instantiation() {
+        // This is how the error of instantiating the auto splitter looks once
+        // the IDE added its context to it.
+        let error = anyhow::anyhow!("unknown import: `env::print` has not been defined")
+            .context("Failed instantiating the WebAssembly module.")
+            .context("Failed starting the auto splitter.");
+
+        let mut info = module_info();
+        info.mark_missing_imports(&error);
+        assert!(status(&info, "print", "function") == ImportStatus::Missing);
+        assert!(status(&info, "timer_start_faster", "function") == ImportStatus::Suspicious);
+        assert!(status(&info, "timer_start", "function") == ImportStatus::Expected);
+    }
+}
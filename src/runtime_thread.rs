@@ -1,58 +1,129 @@
 use std::{
     sync::{atomic::AtomicBool, Arc},
     thread,
-    time::Instant,
+    time::{Duration, Instant},
 };
 
 use dioxus::prelude::*;
 use livesplit_auto_splitting::{AutoSplitter, LogLevel, Timer};
 
-use crate::IdeTimer;
+use crate::{watchdog::WATCHDOG, IdeTimer};
 
 pub static RUNNING: AtomicBool = AtomicBool::new(true);
 
-pub fn run(
-    auto_splitter: SyncSignal<Option<AutoSplitter<IdeTimer>>>,
-    mut timer: SyncSignal<IdeTimer>,
-) {
+/// The outcome of a single call to the auto splitter's `update` function.
+pub struct TickInfo {
+    pub result: anyhow::Result<()>,
+    pub started: Instant,
+    pub time_of_tick: Duration,
+    pub memory_usage: usize,
+    pub handles: u64,
+    pub processes: Vec<(String, String)>,
+}
+
+/// Runs a single tick of the auto splitter while the watchdog watches over it.
+pub fn tick<T: Timer>(auto_splitter: &AutoSplitter<T>, tick_number: u64) -> TickInfo {
+    let mut auto_splitter_lock = auto_splitter.lock();
+    WATCHDOG.begin_tick(tick_number, auto_splitter.interrupt_handle());
+    let started = Instant::now();
+    let result = auto_splitter_lock.update();
+    let time_of_tick = started.elapsed();
+    WATCHDOG.end_tick();
+    let memory_usage = auto_splitter_lock.memory().len();
+    let processes = auto_splitter_lock
+        .attached_processes()
+        .map(|process| {
+            (
+                process.pid().to_string(),
+                process.path().unwrap_or("Unnamed Process").to_owned(),
+            )
+        })
+        .collect();
+    let handles = auto_splitter_lock.handles();
+    drop(auto_splitter_lock);
+
+    TickInfo {
+        result,
+        started,
+        time_of_tick,
+        memory_usage,
+        handles,
+        processes,
+    }
+}
+
+/// Calls `tick` repeatedly, sleeping for the tick rate it returns in between,
+/// until the `running` flag is cleared.
+pub fn run_loop(running: &AtomicBool, mut tick: impl FnMut() -> Duration) {
     let mut next_tick = Instant::now();
-    while RUNNING.load(std::sync::atomic::Ordering::Relaxed) {
+    while running.load(std::sync::atomic::Ordering::Relaxed) {
+        next_tick += tick();
+
+        let now = Instant::now();
+        if let Some(sleep_time) = next_tick.checked_duration_since(now) {
+            thread::sleep(sleep_time);
+        } else {
+            // In this case we missed the next tick already. This likely comes
+            // up when the operating system was suspended for a while. Instead
+            // of trying to catch up, we just reset the next tick to start from
+            // now.
+            next_tick = now;
+        }
+    }
+}
+
+pub fn run(auto_splitter: SyncSignal<Option<AutoSplitter<IdeTimer>>>, timer: SyncSignal<IdeTimer>) {
+    run_loop(&RUNNING, || {
         let tick_rate = if let Some(auto_splitter) = auto_splitter.read().as_ref() {
-            let mut auto_splitter_lock = auto_splitter.lock();
-            let now = Instant::now();
-            let res = auto_splitter_lock.update();
-            let time_of_tick = now.elapsed();
-            let memory_usage = auto_splitter_lock.memory().len();
+            // The tick is counted before it runs, so the timer events it causes
+            // are associated with it.
+            let tick_number = {
+                let mut stats = timer.read().statistics;
+                let stats = &mut *stats.write();
+                stats.ticks += 1;
+                stats.ticks
+            };
+            let tick_info = tick(auto_splitter, tick_number);
+
+            {
+                let mut trace = timer.read().trace;
+                trace.write().record_tick(tick_number, &tick_info);
+            }
+
             {
                 // TODO: Improve perf
                 let mut processes = timer.read().processes;
-                let mut processes = processes.write();
-                processes.clear();
-                auto_splitter_lock.attached_processes().for_each(|process| {
-                    processes.push((
-                        process.pid().to_string(),
-                        process.path().unwrap_or("Unnamed Process").to_owned(),
-                    ));
-                });
+                processes.set(tick_info.processes);
             }
-            let handles = auto_splitter_lock.handles();
-            drop(auto_splitter_lock);
 
             {
                 let mut stats = timer.read().statistics;
                 let stats = &mut *stats.write();
-                stats.memory_usage = memory_usage;
-                stats.handles = handles;
-                if time_of_tick > stats.slowest_tick {
-                    stats.slowest_tick = time_of_tick;
-                }
+                stats.memory_usage = tick_info.memory_usage;
+                stats.handles = tick_info.handles;
                 stats.tick_rate = auto_splitter.tick_rate();
-                stats.avg_tick_secs =
-                    stats.avg_tick_secs * 0.999 + time_of_tick.as_secs_f64() * 0.001;
+                stats.record_tick_time(tick_info.time_of_tick);
+                stats.sample();
             }
 
-            if let Err(e) = res {
-                timer.write().log_runtime(
+            {
+                let mut memory_inspector = timer.read().memory_inspector;
+                if memory_inspector.peek().is_active() {
+                    let auto_splitter_lock = auto_splitter.lock();
+                    let memory = auto_splitter_lock.memory();
+                    if memory_inspector.peek().is_outdated(memory) {
+                        memory_inspector.write().update(memory, tick_number);
+                    }
+                }
+            }
+
+            if let Err(e) = tick_info.result {
+                // The timer is logged to through a copy, as whoever swaps out
+                // the auto splitter may hold on to the timer while it
+                // interrupts this tick, so waiting for the write lock would
+                // deadlock.
+                let mut timer = *timer.read();
+                timer.log_runtime(
                     format_args!("{:?}", e.context("Failed executing the auto splitter.")),
                     LogLevel::Error,
                 )
@@ -73,24 +144,14 @@ pub fn run(
             auto_splitter.tick_rate()
         } else {
             let mut processes = timer.read().processes;
-            let mut processes = processes.write();
-            processes.clear();
+            processes.write().clear();
 
             // Tick at 10 Hz when no runtime is loaded.
-            std::time::Duration::from_secs(1) / 10
+            Duration::from_secs(1) / 10
         };
 
-        next_tick += tick_rate;
+        timer.read().flush_batches();
 
-        let now = Instant::now();
-        if let Some(sleep_time) = next_tick.checked_duration_since(now) {
-            thread::sleep(sleep_time);
-        } else {
-            // In this case we missed the next tick already. This likely comes
-            // up when the operating system was suspended for a while. Instead
-            // of trying to catch up, we just reset the next tick to start from
-            // now.
-            next_tick = now;
-        }
-    }
+        tick_rate
+    });
 }
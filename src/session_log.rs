@@ -0,0 +1,87 @@
+//! Continuously appends the log entries to a file on disk, so they survive the
+//! IDE and can be attached to bug reports.
+
+use std::{
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+
+const FILE_NAME: &str = "auto-splitting-ide";
+
+/// Once the file exceeds this size, it gets rotated.
+const MAX_FILE_SIZE: u64 = 10 << 20;
+
+/// The number of rotated files that are kept in addition to the current one.
+const ROTATED_FILES: usize = 4;
+
+pub struct SessionLog {
+    dir: PathBuf,
+    file: File,
+    size: u64,
+}
+
+impl SessionLog {
+    pub fn open(dir: &Path) -> Result<Self> {
+        fs::create_dir_all(dir).context("Failed creating the session log directory.")?;
+        let path = file_path(dir, 0);
+        let file = open_file(&path)?;
+        let size = file.metadata().map_or(0, |metadata| metadata.len());
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            file,
+            size,
+        })
+    }
+
+    pub fn path(&self) -> PathBuf {
+        file_path(&self.dir, 0)
+    }
+
+    pub fn write_line(&mut self, line: &str) -> Result<()> {
+        if self.size >= MAX_FILE_SIZE {
+            self.rotate()?;
+        }
+        writeln!(self.file, "{line}").context("Failed writing to the session log.")?;
+        self.size += line.len() as u64 + 1;
+        Ok(())
+    }
+
+    /// Shifts all the files by one, dropping the oldest one, and starts a new
+    /// file.
+    fn rotate(&mut self) -> Result<()> {
+        let _ = fs::remove_file(file_path(&self.dir, ROTATED_FILES));
+        for index in (0..ROTATED_FILES).rev() {
+            let _ = fs::rename(file_path(&self.dir, index), file_path(&self.dir, index + 1));
+        }
+        self.file = open_file(&file_path(&self.dir, 0))?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+/// The directory that the session logs are stored in if none is specified.
+pub fn default_dir() -> Result<PathBuf> {
+    let mut path = dirs::data_local_dir().context("There is no local data directory.")?;
+    path.push("auto-splitting-ide");
+    path.push("logs");
+    Ok(path)
+}
+
+fn file_path(dir: &Path, index: usize) -> PathBuf {
+    if index == 0 {
+        dir.join(format!("{FILE_NAME}.log"))
+    } else {
+        dir.join(format!("{FILE_NAME}.{index}.log"))
+    }
+}
+
+fn open_file(path: &Path) -> Result<File> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .context("Failed opening the session log.")
+}
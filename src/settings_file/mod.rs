@@ -0,0 +1,184 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use livesplit_auto_splitting::settings::{self, Value};
+use serde_json::{Map as JsonMap, Number, Value as JsonValue};
+
+mod xml;
+
+/// Loads a settings map from either a JSON file or, based on the extension, a
+/// LiveSplit splits, layout or XML file containing `AutoSplitterSettings`.
+pub fn load(path: &Path) -> Result<settings::Map> {
+    if is_xml(path) {
+        let data = fs::read_to_string(path).context("Failed reading the settings file.")?;
+        return xml::parse(&data).context("Failed parsing the auto splitter settings.");
+    }
+
+    let data = fs::read(path).context("Failed reading the settings file.")?;
+    let json: JsonValue =
+        serde_json::from_slice(&data).context("Failed parsing the settings file.")?;
+    let JsonValue::Object(object) = json else {
+        bail!("The settings file needs to contain a JSON object.");
+    };
+    Ok(map_from_json(object))
+}
+
+/// Saves the settings map as JSON or, based on the extension, in LiveSplit's
+/// `AutoSplitterSettings` XML format. Splits and layout files are refused, as
+/// the settings would replace everything else in them.
+pub fn save(path: &Path, map: &settings::Map) -> Result<()> {
+    if has_extension(path, &["lss", "lsl"]) {
+        bail!(
+            "The settings can't be exported to a LiveSplit splits or layout file, as that \
+            would replace its contents. Export them to an XML file instead."
+        );
+    }
+    let data = if is_xml(path) {
+        xml::serialize(map)?.into_bytes()
+    } else {
+        serde_json::to_vec_pretty(&JsonValue::Object(map_to_json(map)))
+            .context("Failed serializing the settings.")?
+    };
+    fs::write(path, data).context("Failed writing the settings file.")?;
+    Ok(())
+}
+
+fn is_xml(path: &Path) -> bool {
+    has_extension(path, &["xml", "lss", "lsl"])
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension().is_some_and(|ext| {
+        extensions
+            .iter()
+            .any(|extension| ext.eq_ignore_ascii_case(extension))
+    })
+}
+
+/// Loads the settings that were saved for the auto splitter at the given path
+/// in a previous session, if there are any.
+pub fn load_saved(wasm_path: &Path) -> Result<Option<settings::Map>> {
+    let path = saved_path(wasm_path)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    load(&path).map(Some)
+}
+
+/// Saves the settings of the auto splitter at the given path, so they can be
+/// restored in the next session. An empty settings map removes the saved
+/// settings instead, as that is what the auto splitter starts with anyway.
+pub fn store_saved(wasm_path: &Path, map: &settings::Map) -> Result<()> {
+    if map.is_empty() {
+        return forget_saved(wasm_path);
+    }
+    let path = saved_path(wasm_path)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("Failed creating the settings directory.")?;
+    }
+    save(&path, map)
+}
+
+pub fn forget_saved(wasm_path: &Path) -> Result<()> {
+    match fs::remove_file(saved_path(wasm_path)?) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => {
+            Err(e).context("Failed removing the saved settings.")
+        }
+        _ => Ok(()),
+    }
+}
+
+fn saved_path(wasm_path: &Path) -> Result<PathBuf> {
+    let mut path = dirs::config_dir().context("There is no configuration directory.")?;
+    path.push("auto-splitting-ide");
+    path.push("settings");
+
+    // The settings are keyed by the path of the auto splitter, so they survive
+    // rebuilding it. FNV-1a is used as the hash needs to be stable across
+    // sessions and Rust versions.
+    let wasm_path = wasm_path
+        .canonicalize()
+        .unwrap_or_else(|_| wasm_path.to_path_buf());
+    let hash = wasm_path
+        .to_string_lossy()
+        .bytes()
+        .fold(0xcbf29ce484222325u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        });
+    path.push(format!("{hash:016x}.json"));
+
+    Ok(path)
+}
+
+fn map_to_json(map: &settings::Map) -> JsonMap<String, JsonValue> {
+    map.iter()
+        .filter_map(|(key, value)| Some((key.to_owned(), value_to_json(value)?)))
+        .collect()
+}
+
+fn value_to_json(value: &Value) -> Option<JsonValue> {
+    Some(match value {
+        Value::Map(map) => JsonValue::Object(map_to_json(map)),
+        Value::List(list) => JsonValue::Array(list.iter().filter_map(value_to_json).collect()),
+        Value::Bool(value) => JsonValue::Bool(*value),
+        Value::I64(value) => JsonValue::Number((*value).into()),
+        Value::F64(value) => JsonValue::Number(Number::from_f64(*value)?),
+        Value::String(value) => JsonValue::String((**value).to_owned()),
+        _ => return None,
+    })
+}
+
+fn map_from_json(object: JsonMap<String, JsonValue>) -> settings::Map {
+    let mut map = settings::Map::new();
+    for (key, value) in object {
+        if let Some(value) = value_from_json(value) {
+            map.insert(key.into(), value);
+        }
+    }
+    map
+}
+
+fn value_from_json(value: JsonValue) -> Option<Value> {
+    Some(match value {
+        JsonValue::Null => return None,
+        JsonValue::Bool(value) => Value::Bool(value),
+        JsonValue::Number(number) => match number.as_i64() {
+            Some(value) => Value::I64(value),
+            None => Value::F64(number.as_f64()?),
+        },
+        JsonValue::String(value) => Value::String(value.into()),
+        JsonValue::Array(values) => {
+            let mut list = settings::List::new();
+            for value in values.into_iter().filter_map(value_from_json) {
+                list.push(value);
+            }
+            Value::List(list)
+        }
+        JsonValue::Object(object) => Value::Map(map_from_json(object)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refuses_to_overwrite_splits_and_layouts() {
+        let dir = std::env::temp_dir().join(format!("settings-file-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for name in ["run.lss", "layout.LSL"] {
+            let path = dir.join(name);
+            fs::write(&path, "<Run />").unwrap();
+            assert!(save(&path, &settings::Map::new()).is_err());
+            assert_eq!(fs::read_to_string(&path).unwrap(), "<Run />");
+        }
+
+        let path = dir.join("settings.xml");
+        save(&path, &settings::Map::new()).unwrap();
+        assert!(load(&path).unwrap().is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,330 @@
+//! The `AutoSplitterSettings` format that LiveSplit One and LiveSplit store in
+//! their splits (`.lss`) and layout (`.lsl`) files:
+//!
+//! ```xml
+//! <AutoSplitterSettings>
+//!   <Setting id="start" type="bool" value="True" />
+//!   <Setting id="category" type="string" value="Any%" />
+//!   <Setting id="levels" type="list">
+//!     <Setting type="i64" value="3" />
+//!   </Setting>
+//! </AutoSplitterSettings>
+//! ```
+
+use std::borrow::Cow;
+
+use anyhow::{bail, Context, Result};
+use livesplit_auto_splitting::settings::{self, Value};
+use quick_xml::{
+    escape,
+    events::{BytesDecl, BytesEnd, BytesStart, Event},
+    Reader, Writer,
+};
+
+const ROOT: &str = "AutoSplitterSettings";
+const SETTING: &str = "Setting";
+
+/// Parses the settings map from a document that contains an
+/// `AutoSplitterSettings` element anywhere within it, so whole splits and
+/// layout files can be imported.
+pub fn parse(xml: &str) -> Result<settings::Map> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(start) if start.name().as_ref() == ROOT.as_bytes() => {
+                return read_map(&mut reader);
+            }
+            Event::Empty(start) if start.name().as_ref() == ROOT.as_bytes() => {
+                return Ok(settings::Map::new());
+            }
+            Event::Eof => bail!("The file doesn't contain any auto splitter settings."),
+            _ => {}
+        }
+    }
+}
+
+pub fn serialize(map: &settings::Map) -> Result<String> {
+    let mut writer = Writer::new_with_indent(Vec::new(), b' ', 2);
+    writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+    writer.write_event(Event::Start(BytesStart::new(ROOT)))?;
+    for (key, value) in map.iter() {
+        write_value(&mut writer, Some(key), value)?;
+    }
+    writer.write_event(Event::End(BytesEnd::new(ROOT)))?;
+    Ok(String::from_utf8(writer.into_inner())?)
+}
+
+fn read_map(reader: &mut Reader<&[u8]>) -> Result<settings::Map> {
+    let mut map = settings::Map::new();
+    for (key, value) in read_children(reader)? {
+        if let Some(key) = key {
+            map.insert(key.into(), value);
+        }
+    }
+    Ok(map)
+}
+
+fn read_list(reader: &mut Reader<&[u8]>) -> Result<settings::List> {
+    let mut list = settings::List::new();
+    for (_, value) in read_children(reader)? {
+        list.push(value);
+    }
+    Ok(list)
+}
+
+/// Reads all the `Setting` elements until the end of the current element.
+fn read_children(reader: &mut Reader<&[u8]>) -> Result<Vec<(Option<String>, Value)>> {
+    let mut children = Vec::new();
+    loop {
+        match reader.read_event()? {
+            Event::Start(start) => {
+                if start.name().as_ref() == SETTING.as_bytes() {
+                    children.extend(read_setting(reader, &start, false)?);
+                } else {
+                    reader.read_to_end(start.name())?;
+                }
+            }
+            Event::Empty(start) if start.name().as_ref() == SETTING.as_bytes() => {
+                children.extend(read_setting(reader, &start, true)?);
+            }
+            Event::End(_) => return Ok(children),
+            Event::Eof => bail!("The auto splitter settings are incomplete."),
+            _ => {}
+        }
+    }
+}
+
+fn read_setting(
+    reader: &mut Reader<&[u8]>,
+    start: &BytesStart<'_>,
+    is_empty: bool,
+) -> Result<Option<(Option<String>, Value)>> {
+    let id = attribute(start, "id")?;
+    let ty = attribute(start, "type")?.unwrap_or_default();
+
+    let value = match &*ty {
+        "map" => Value::Map(if is_empty {
+            settings::Map::new()
+        } else {
+            read_map(reader)?
+        }),
+        "list" => Value::List(if is_empty {
+            settings::List::new()
+        } else {
+            read_list(reader)?
+        }),
+        _ => {
+            // LiveSplit stores the value as an attribute, but the older
+            // format of storing it as the text content is supported as well.
+            let text = match attribute(start, "value")? {
+                Some(value) => {
+                    if !is_empty {
+                        reader.read_to_end(start.name())?;
+                    }
+                    value
+                }
+                None if !is_empty => {
+                    escape::unescape(&reader.read_text(start.name())?)?.into_owned()
+                }
+                None => String::new(),
+            };
+            let trimmed = text.trim();
+
+            match &*ty {
+                "bool" => Value::Bool(trimmed.eq_ignore_ascii_case("true")),
+                "i64" => Value::I64(
+                    trimmed
+                        .parse()
+                        .with_context(|| format!("Invalid integer setting {text:?}."))?,
+                ),
+                "f64" => Value::F64(
+                    trimmed
+                        .parse()
+                        .with_context(|| format!("Invalid float setting {text:?}."))?,
+                ),
+                "string" => Value::String(text.into()),
+                _ => return Ok(None),
+            }
+        }
+    };
+
+    Ok(Some((id, value)))
+}
+
+fn attribute(start: &BytesStart<'_>, name: &str) -> Result<Option<String>> {
+    Ok(match start.try_get_attribute(name)? {
+        Some(attribute) => Some(attribute.unescape_value()?.into_owned()),
+        None => None,
+    })
+}
+
+fn write_value(writer: &mut Writer<Vec<u8>>, key: Option<&str>, value: &Value) -> Result<()> {
+    let mut start = BytesStart::new(SETTING);
+    if let Some(key) = key {
+        start.push_attribute(("id", key));
+    }
+
+    match value {
+        Value::Map(map) => {
+            start.push_attribute(("type", "map"));
+            writer.write_event(Event::Start(start))?;
+            for (key, value) in map.iter() {
+                write_value(writer, Some(key), value)?;
+            }
+            writer.write_event(Event::End(BytesEnd::new(SETTING)))?;
+        }
+        Value::List(list) => {
+            start.push_attribute(("type", "list"));
+            writer.write_event(Event::Start(start))?;
+            for value in list.iter() {
+                write_value(writer, None, value)?;
+            }
+            writer.write_event(Event::End(BytesEnd::new(SETTING)))?;
+        }
+        _ => {
+            let (ty, value) = match value {
+                Value::Bool(value) => {
+                    ("bool", Cow::Borrowed(if *value { "True" } else { "False" }))
+                }
+                Value::I64(value) => ("i64", Cow::Owned(value.to_string())),
+                Value::F64(value) => ("f64", Cow::Owned(value.to_string())),
+                Value::String(value) => ("string", Cow::Borrowed(&**value)),
+                _ => return Ok(()),
+            };
+            start.push_attribute(("type", ty));
+            start.push_attribute(("value", &*value));
+            writer.write_event(Event::Empty(start))?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::settings_file::map_to_json;
+
+    fn string(value: &str) -> Value {
+        Value::String(Arc::from(value))
+    }
+
+    #[test]
+    fn round_trips_nested_maps_and_lists() {
+        let mut inner = settings::Map::new();
+        inner.insert("enabled".into(), Value::Bool(false));
+        inner.insert("name".into(), string("Chapter <1> & \"2\""));
+
+        let mut nested_list = settings::List::new();
+        nested_list.push(Value::F64(-0.5));
+
+        let mut list = settings::List::new();
+        list.push(Value::I64(3));
+        list.push(Value::Map(inner.clone()));
+        list.push(Value::List(nested_list));
+        list.push(Value::List(settings::List::new()));
+
+        let mut map = settings::Map::new();
+        map.insert("start".into(), Value::Bool(true));
+        map.insert("count".into(), Value::I64(i64::MIN));
+        map.insert("ratio".into(), Value::F64(1.25));
+        map.insert("category".into(), string("  Any%  "));
+        map.insert("inner".into(), Value::Map(inner));
+        map.insert("empty".into(), Value::Map(settings::Map::new()));
+        map.insert("levels".into(), Value::List(list));
+
+        let parsed = parse(&serialize(&map).unwrap()).unwrap();
+        assert_eq!(map_to_json(&parsed), map_to_json(&map));
+    }
+
+    #[test]
+    fn parses_settings_from_splits_file() {
+        let lss = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Run version="1.7.0">
+  <GameIcon />
+  <GameName>Celeste</GameName>
+  <CategoryName>Any%</CategoryName>
+  <Metadata>
+    <Run id="" />
+    <Platform usesEmulator="False">
+    </Platform>
+    <Region>
+    </Region>
+    <Variables />
+  </Metadata>
+  <Offset>00:00:00</Offset>
+  <AttemptCount>0</AttemptCount>
+  <AttemptHistory />
+  <Segments>
+    <Segment>
+      <Name>Forsaken City</Name>
+      <Icon />
+      <SplitTimes>
+        <SplitTime name="Personal Best" />
+      </SplitTimes>
+      <BestSegmentTime />
+      <SegmentHistory />
+    </Segment>
+  </Segments>
+  <AutoSplitterSettings>
+    <Setting id="start" type="bool" value="True" />
+    <Setting id="reset" type="bool" value="False" />
+    <Setting id="category" type="string" value="Any&amp;%" />
+    <Setting id="levels" type="list">
+      <Setting type="i64" value="3" />
+      <Setting type="f64" value="2.5" />
+    </Setting>
+    <Setting id="chapters" type="map">
+      <Setting id="prologue" type="bool" value="true" />
+    </Setting>
+    <Setting id="legacy" type="string">Old &lt;format&gt;</Setting>
+    <Setting id="unknown" type="something" value="ignored" />
+  </AutoSplitterSettings>
+</Run>
+"#;
+        let map = parse(lss).unwrap();
+        assert_eq!(
+            serde_json::Value::Object(map_to_json(&map)),
+            serde_json::json!({
+                "start": true,
+                "reset": false,
+                "category": "Any&%",
+                "levels": [3, 2.5],
+                "chapters": { "prologue": true },
+                "legacy": "Old <format>",
+            }),
+        );
+    }
+
+    #[test]
+    fn parses_empty_settings() {
+        let map = parse("<Run><AutoSplitterSettings /></Run>").unwrap();
+        assert!(map.is_empty());
+
+        let mut empty_containers = settings::Map::new();
+        empty_containers.insert("list".into(), Value::List(settings::List::new()));
+        empty_containers.insert("map".into(), Value::Map(settings::Map::new()));
+        let map = parse(
+            r#"<AutoSplitterSettings>
+                <Setting id="list" type="list" />
+                <Setting id="map" type="map" />
+            </AutoSplitterSettings>"#,
+        )
+        .unwrap();
+        assert_eq!(map_to_json(&map), map_to_json(&empty_containers));
+    }
+
+    #[test]
+    fn rejects_invalid_documents() {
+        assert!(parse("<Run><GameName>Celeste</GameName></Run>").is_err());
+        assert!(parse(
+            r#"<AutoSplitterSettings><Setting id="x" type="i64" value="nope" /></AutoSplitterSettings>"#
+        )
+        .is_err());
+        assert!(parse(r#"<AutoSplitterSettings><Setting id="x" type="map">"#).is_err());
+    }
+}
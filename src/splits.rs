@@ -0,0 +1,118 @@
+use std::{fs, path::Path};
+
+use anyhow::{Context, Result};
+use quick_xml::{events::Event, Reader};
+
+/// Loads the names of the segments either from a LiveSplit splits file or from
+/// a text file that lists one segment per line.
+pub fn load(path: &Path) -> Result<Vec<String>> {
+    let data = fs::read_to_string(path).context("Failed reading the splits file.")?;
+
+    if path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("lss"))
+    {
+        parse_lss(&data).context("Failed parsing the splits file.")
+    } else {
+        Ok(data
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(ToOwned::to_owned)
+            .collect())
+    }
+}
+
+fn parse_lss(data: &str) -> Result<Vec<String>> {
+    let mut reader = Reader::from_str(data);
+    reader.config_mut().trim_text(true);
+
+    let mut path = Vec::new();
+    let mut segments = Vec::new();
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(start) => {
+                let name = start.name();
+                if path == ["Run", "Segments", "Segment"] && name.as_ref() == b"Name" {
+                    segments.push(read_name(&mut reader)?);
+                } else {
+                    path.push(String::from_utf8_lossy(name.as_ref()).into_owned());
+                }
+            }
+            Event::Empty(start)
+                if path == ["Run", "Segments", "Segment"] && start.name().as_ref() == b"Name" =>
+            {
+                segments.push(String::new());
+            }
+            Event::End(_) => {
+                path.pop();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(segments)
+}
+
+/// Reads the text of a `Name` element, which LiveSplit writes as CDATA, but
+/// other tools may write as escaped text.
+fn read_name(reader: &mut Reader<&[u8]>) -> Result<String> {
+    let mut name = String::new();
+    loop {
+        match reader.read_event()? {
+            Event::Text(text) => name.push_str(&text.unescape()?),
+            Event::CData(text) => name.push_str(&text.decode()?),
+            Event::End(_) => return Ok(name),
+            Event::Eof => anyhow::bail!("The name of a segment isn't closed."),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_segment_names() {
+        let data = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Run version="1.7.0">
+  <GameName><![CDATA[Game]]></GameName>
+  <CategoryName>Any%</CategoryName>
+  <Segments>
+    <Segment>
+      <Name><![CDATA[Tom & Jerry's <Level>]]></Name>
+      <Icon />
+      <SplitTimes>
+        <SplitTime name="Personal Best" />
+      </SplitTimes>
+    </Segment>
+    <Segment>
+      <Name>Fish &amp; Chips</Name>
+      <Icon />
+    </Segment>
+    <Segment>
+      <Name />
+    </Segment>
+    <Segment>
+      <Name></Name>
+    </Segment>
+  </Segments>
+  <AutoSplitterSettings>
+    <Name>Not a segment</Name>
+  </AutoSplitterSettings>
+</Run>"#;
+
+        assert_eq!(
+            parse_lss(data).unwrap(),
+            ["Tom & Jerry's <Level>", "Fish & Chips", "", ""],
+        );
+    }
+
+    #[test]
+    fn rejects_unclosed_names() {
+        assert!(parse_lss("<Run><Segments><Segment><Name>Level").is_err());
+    }
+}
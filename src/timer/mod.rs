@@ -0,0 +1,562 @@
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::Duration,
+};
+
+use dioxus::prelude::*;
+use livesplit_auto_splitting::{
+    settings, AutoSplitter, CompiledAutoSplitter, Config, LogLevel, Runtime, Timer, TimerState,
+};
+
+use crate::{
+    debug_info, module_info::ModuleInfo, settings_file, trace::TickTrace, LogEntries, LogEntry,
+    MemoryInspector, StatisticsData, TimerEventEntry, TimerEventKind, TimerEventSource,
+    TimerEvents, VariableHistory, Widgets,
+};
+
+mod model;
+
+pub use model::*;
+
+#[derive(Copy, Clone)]
+pub struct IdeTimer {
+    pub model: SyncSignal<TimerModel>,
+    pub variable_history: SyncSignal<VariableHistory>,
+    pub processes: SyncSignal<Vec<(String, String)>>,
+    pub settings_widgets: SyncSignal<Widgets>,
+    pub settings_map: SyncSignal<settings::Map>,
+    pub settings_restored: SyncSignal<bool>,
+    pub logs: SyncSignal<LogEntries>,
+    /// The log entries of the current tick. They are added to the logs all at
+    /// once after the tick, so the UI doesn't update for every single one.
+    pub log_batch: SyncSignal<Vec<LogEntry>>,
+    pub timer_events: SyncSignal<TimerEvents>,
+    /// The timer events of the current tick. Like the log entries, they are
+    /// added to the timeline all at once after the tick.
+    pub timer_event_batch: SyncSignal<Vec<TimerEventEntry>>,
+    pub wasm_path: SyncSignal<Option<PathBuf>>,
+    /// The source files that the auto splitter got compiled from.
+    pub source_files: SyncSignal<Vec<PathBuf>>,
+    pub module_info: SyncSignal<Option<ModuleInfo>>,
+    pub load_options: SyncSignal<LoadOptions>,
+    pub memory_inspector: SyncSignal<MemoryInspector>,
+    pub statistics: SyncSignal<StatisticsData>,
+    pub trace: SyncSignal<TickTrace>,
+    /// Who calls the `Timer` functions of this instance. The auto splitter
+    /// gets its own copy of the timer, so everything else is a manual call.
+    pub source: TimerEventSource,
+}
+
+enum Load<'a> {
+    File(&'a Path, Option<settings::Map>),
+    Reload,
+    Restart,
+}
+
+/// What happens to the state that the previous auto splitter left behind when
+/// a new one takes over.
+#[derive(Copy, Clone, PartialEq)]
+pub struct LoadBehavior {
+    pub reset_timer: bool,
+    pub clear_variables: bool,
+}
+
+/// The behavior for each of the ways an auto splitter can be started. By
+/// default, opening a file starts from scratch, while reloading keeps
+/// everything, so a split condition can be iterated on in the middle of a run.
+#[derive(Copy, Clone, PartialEq)]
+pub struct LoadOptions {
+    pub load: LoadBehavior,
+    pub reload: LoadBehavior,
+    pub restart: LoadBehavior,
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        Self {
+            load: LoadBehavior {
+                reset_timer: true,
+                clear_variables: true,
+            },
+            reload: LoadBehavior {
+                reset_timer: false,
+                clear_variables: false,
+            },
+            restart: LoadBehavior {
+                reset_timer: false,
+                clear_variables: true,
+            },
+        }
+    }
+}
+
+impl LoadOptions {
+    fn behavior(&self, load: &Load<'_>) -> LoadBehavior {
+        match load {
+            Load::File(..) => self.load,
+            Load::Reload => self.reload,
+            Load::Restart => self.restart,
+        }
+    }
+}
+
+impl IdeTimer {
+    pub fn load_file(
+        &self,
+        file: &Path,
+        settings_map: Option<settings::Map>,
+        runtime: SyncSignal<Runtime>,
+        module: SyncSignal<Option<CompiledAutoSplitter>>,
+        auto_splitter: SyncSignal<Option<AutoSplitter<IdeTimer>>>,
+    ) {
+        self.load(
+            Load::File(file, settings_map),
+            runtime,
+            module,
+            auto_splitter,
+        );
+    }
+
+    pub fn reload(
+        &self,
+        runtime: SyncSignal<Runtime>,
+        module: SyncSignal<Option<CompiledAutoSplitter>>,
+        auto_splitter: SyncSignal<Option<AutoSplitter<IdeTimer>>>,
+    ) {
+        self.load(Load::Reload, runtime, module, auto_splitter);
+    }
+
+    pub fn restart(
+        &self,
+        runtime: SyncSignal<Runtime>,
+        module: SyncSignal<Option<CompiledAutoSplitter>>,
+        auto_splitter: SyncSignal<Option<AutoSplitter<IdeTimer>>>,
+    ) {
+        self.load(Load::Restart, runtime, module, auto_splitter);
+    }
+
+    /// Stops the auto splitter and forgets about its module.
+    pub fn close(
+        &self,
+        mut module: SyncSignal<Option<CompiledAutoSplitter>>,
+        auto_splitter: SyncSignal<Option<AutoSplitter<IdeTimer>>>,
+    ) {
+        module.set(None);
+        swap_auto_splitter(auto_splitter, None, || {
+            if self.wasm_path.peek().is_some() {
+                { self.wasm_path }.set(None);
+            }
+        });
+    }
+
+    fn load(
+        &self,
+        load: Load<'_>,
+        runtime: SyncSignal<Runtime>,
+        mut module: SyncSignal<Option<CompiledAutoSplitter>>,
+        auto_splitter: SyncSignal<Option<AutoSplitter<IdeTimer>>>,
+    ) {
+        let wasm_path = match &load {
+            Load::File(path, _) => Some(path.to_path_buf()),
+            Load::Reload | Load::Restart => self.wasm_path.peek().clone(),
+        };
+
+        let settings_map = if let Load::File(path, settings_map) = &load {
+            let restored = if settings_map.is_none() {
+                settings_file::load_saved(path).unwrap_or_else(|e| {
+                    { self.logs }.write().push_level(
+                        format!("{:?}", e.context("Failed restoring the saved settings.")),
+                        LogLevel::Warning,
+                    );
+                    None
+                })
+            } else {
+                None
+            };
+            { self.settings_restored }.set(restored.is_some());
+            settings_map.clone().or(restored)
+        } else {
+            auto_splitter.read().as_ref().map(|r| r.settings_map())
+        };
+
+        let mut succeeded = true;
+
+        if let (Load::File(..) | Load::Reload, Some(path)) = (&load, &wasm_path) {
+            let compiled = read_file(path).and_then(|data| {
+                self.read_source_files(&data);
+                self.read_module_info(&data);
+                compile(&runtime.read(), &data)
+            });
+            module.set(match compiled {
+                Ok(module) => Some(module),
+                Err(e) => {
+                    succeeded = false;
+                    { self.logs }
+                        .write()
+                        .push_level(format!("{e:?}"), LogLevel::Error);
+                    None
+                }
+            });
+
+            // self.module_modified_time = fs::metadata(path).ok().and_then(|m| m.modified().ok());
+        }
+
+        let new_auto_splitter = if let Some(module) = &*module.read() {
+            match module
+                .instantiate(
+                    IdeTimer {
+                        source: TimerEventSource::AutoSplitter,
+                        ..*self
+                    },
+                    settings_map,
+                    None,
+                )
+                .context("Failed starting the auto splitter.")
+            {
+                Ok(r) => Some(r),
+                Err(e) => {
+                    succeeded = false;
+                    if let Some(module_info) = &mut *{ self.module_info }.write() {
+                        module_info.mark_missing_imports(&e);
+                    }
+                    { self.logs }
+                        .write()
+                        .push_level(format!("{e:?}"), LogLevel::Error);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        // The timer is reset before the new auto splitter gets to tick, so
+        // nothing it does in its first tick gets undone.
+        let behavior = new_auto_splitter
+            .is_some()
+            .then(|| self.load_options.peek().behavior(&load));
+        let new_settings_map = new_auto_splitter
+            .as_ref()
+            .map(|r| r.settings_map())
+            .unwrap_or_else(settings::Map::new);
+        swap_auto_splitter(auto_splitter, new_auto_splitter, || {
+            // The path and the settings map are updated along with the auto
+            // splitter, so the settings map never belongs to a different auto
+            // splitter than the one that is loaded, which matters for saving
+            // it.
+            if *self.wasm_path.peek() != wasm_path {
+                { self.wasm_path }.set(wasm_path);
+            }
+            { self.settings_map }.set(new_settings_map);
+
+            if let Some(behavior) = behavior {
+                if behavior.reset_timer {
+                    let event = { self.model }.write().reset();
+                    self.log_event(event);
+                }
+                if behavior.reset_timer || behavior.clear_variables {
+                    { self.model }.write().variables.clear();
+                    { self.variable_history }.write().clear();
+                }
+            }
+        });
+
+        { self.statistics }.write().reset();
+
+        if succeeded {
+            { self.logs }.write().push_level(
+                match load {
+                    Load::File(..) => "Auto splitter loaded.",
+                    Load::Reload => "Auto splitter reloaded.",
+                    Load::Restart => "Auto splitter restarted.",
+                }
+                .into(),
+                LogLevel::Info,
+            );
+        }
+    }
+
+    fn read_source_files(&self, data: &[u8]) {
+        let source_files = debug_info::source_files(data).unwrap_or_else(|e| {
+            { self.logs }.write().push_level(
+                format!("{:?}", e.context("Failed reading the debug information.")),
+                LogLevel::Warning,
+            );
+            Vec::new()
+        });
+        if *self.source_files.peek() != source_files {
+            { self.source_files }.set(source_files);
+        }
+    }
+
+    /// Warns about imports that the runtime most likely doesn't provide, as
+    /// instantiating the auto splitter would fail because of them. The runtime
+    /// can't be asked which functions it provides, so they are compared to the
+    /// functions it is known to provide.
+    fn read_module_info(&self, data: &[u8]) {
+        let module_info = match ModuleInfo::parse(data) {
+            Ok(module_info) => module_info,
+            Err(e) => {
+                { self.logs }.write().push_level(
+                    format!("{:?}", e.context("Failed reading the module information.")),
+                    LogLevel::Warning,
+                );
+                { self.module_info }.set(None);
+                return;
+            }
+        };
+        let unsupported = module_info
+            .unsupported_imports()
+            .map(|import| format!("`{}::{}` ({})", import.module, import.name, import.kind))
+            .collect::<Vec<_>>();
+        if !unsupported.is_empty() {
+            { self.logs }.write().push_level(
+                format!(
+                    "The auto splitter imports {} that the runtime most likely doesn't \
+                    provide: {}",
+                    if unsupported.len() == 1 {
+                        "an item"
+                    } else {
+                        "items"
+                    },
+                    unsupported.join(", "),
+                ),
+                LogLevel::Warning,
+            );
+        }
+        { self.module_info }.set(Some(module_info));
+    }
+
+    fn timer_state(&self) -> TimerState {
+        self.model.read().state
+    }
+
+    pub fn pause(&mut self) {
+        if self.timer_state() == TimerState::Running {
+            let event = self.model.write().pause();
+            self.log_event(event);
+        }
+    }
+
+    pub fn resume(&mut self) {
+        if self.timer_state() == TimerState::Paused {
+            let event = self.model.write().resume();
+            self.log_event(event);
+        }
+    }
+
+    pub fn undo_all_pauses(&mut self) {
+        if matches!(self.timer_state(), TimerState::Running | TimerState::Paused) {
+            let event = self.model.write().undo_all_pauses();
+            self.log_event(event);
+        }
+    }
+
+    /// Moves the batched log entries into the logs and the batched timer
+    /// events into the timeline.
+    pub fn flush_batches(&self) {
+        if !self.log_batch.read().is_empty() {
+            let entries = std::mem::take(&mut *{ self.log_batch }.write());
+            { self.logs }.write().extend(entries);
+        }
+        if !self.timer_event_batch.read().is_empty() {
+            let entries = std::mem::take(&mut *{ self.timer_event_batch }.write());
+            { self.timer_events }.write().extend(entries);
+        }
+    }
+
+    /// Records a call of one of the `Timer` functions in the timeline.
+    fn record(&self, kind: TimerEventKind, applied: bool) {
+        let tick = self.statistics.peek().ticks;
+        { self.trace }
+            .write()
+            .record_event(tick, &kind, self.source, applied);
+        { self.timer_event_batch }
+            .write()
+            .push(TimerEventEntry::new(tick, &kind, self.source, applied));
+    }
+
+    fn log_event(&self, event: Option<TimerEvent>) {
+        if let Some(event) = event {
+            { self.log_batch }
+                .write()
+                .push(LogEntry::runtime(event.to_string(), LogLevel::Debug));
+        }
+    }
+}
+
+impl Timer for IdeTimer {
+    fn state(&self) -> TimerState {
+        self.timer_state()
+    }
+
+    fn start(&mut self) {
+        let event = if self.timer_state() == TimerState::NotRunning {
+            self.model.write().start()
+        } else {
+            None
+        };
+        self.record(TimerEventKind::Start, event.is_some());
+        self.log_event(event);
+    }
+
+    fn split(&mut self) {
+        let event = if self.timer_state() == TimerState::Running {
+            self.model.write().split()
+        } else {
+            None
+        };
+        self.record(TimerEventKind::Split, event.is_some());
+        self.log_event(event);
+    }
+
+    fn skip_split(&mut self) {
+        let event = if self.timer_state() == TimerState::Running {
+            self.model.write().skip_split()
+        } else {
+            None
+        };
+        self.record(TimerEventKind::SkipSplit, event.is_some());
+        self.log_event(event);
+    }
+
+    fn undo_split(&mut self) {
+        let event = if matches!(self.timer_state(), TimerState::Running | TimerState::Ended) {
+            self.model.write().undo_split()
+        } else {
+            None
+        };
+        self.record(TimerEventKind::UndoSplit, event.is_some());
+        self.log_event(event);
+    }
+
+    fn reset(&mut self) {
+        let event = if self.timer_state() != TimerState::NotRunning {
+            self.model.write().reset()
+        } else {
+            None
+        };
+        if event.is_some() {
+            { self.variable_history }.write().clear();
+        }
+        self.record(TimerEventKind::Reset, event.is_some());
+        self.log_event(event);
+    }
+
+    fn set_game_time(&mut self, time: time::Duration) {
+        let model = self.model.read();
+        let applied = model.game_time != time || model.game_time_state != GameTimeState::Running;
+        drop(model);
+        if applied {
+            self.model.write().set_game_time(time);
+        }
+        self.record(TimerEventKind::SetGameTime(time), applied);
+    }
+
+    fn pause_game_time(&mut self) {
+        let applied = self.model.read().game_time_state != GameTimeState::Paused;
+        if applied {
+            self.model.write().pause_game_time();
+        }
+        self.record(TimerEventKind::PauseGameTime, applied);
+    }
+
+    fn resume_game_time(&mut self) {
+        let applied = self.model.read().game_time_state != GameTimeState::Running;
+        if applied {
+            self.model.write().resume_game_time();
+        }
+        self.record(TimerEventKind::ResumeGameTime, applied);
+    }
+
+    fn set_variable(&mut self, key: &str, value: &str) {
+        let applied = !self.model.read().is_variable(key, value);
+        if applied {
+            self.model.write().set_variable(key, value);
+            let tick = self.statistics.peek().ticks;
+            { self.variable_history }.write().push(tick, key, value);
+        }
+        self.record(
+            TimerEventKind::SetVariable {
+                key: key.into(),
+                value: value.into(),
+            },
+            applied,
+        );
+    }
+
+    fn log_auto_splitter(&mut self, message: fmt::Arguments<'_>) {
+        self.log_batch
+            .write()
+            .push(LogEntry::auto_splitter(format!("{message}")));
+    }
+
+    fn log_runtime(&mut self, message: fmt::Arguments<'_>, log_level: LogLevel) {
+        self.log_batch
+            .write()
+            .push(LogEntry::runtime(format!("{message}"), log_level));
+    }
+
+    fn current_split_index(&self) -> Option<usize> {
+        self.model.read().current_split_index()
+    }
+
+    fn segment_splitted(&self, index: usize) -> Option<bool> {
+        self.model.read().segment_splitted(index)
+    }
+}
+
+/// The runtime thread holds on to the auto splitter for as long as a tick runs,
+/// so the new one can't be swapped in before that. The outgoing auto splitter
+/// gets dropped anyway, so it is interrupted until the swap happened, instead
+/// of waiting for the tick to finish, which may never happen if it's stuck.
+/// `prepare` is called right before the swap.
+fn swap_auto_splitter(
+    mut auto_splitter: SyncSignal<Option<AutoSplitter<IdeTimer>>>,
+    new_auto_splitter: Option<AutoSplitter<IdeTimer>>,
+    prepare: impl FnOnce(),
+) {
+    let interrupt_handle = auto_splitter.peek().as_ref().map(|r| r.interrupt_handle());
+    let swapped = &AtomicBool::new(false);
+    thread::scope(|s| {
+        if let Some(interrupt_handle) = interrupt_handle {
+            s.spawn(move || {
+                // A tick may start right after an interrupt, so this is
+                // repeated.
+                while !swapped.load(Ordering::Relaxed) {
+                    interrupt_handle.interrupt();
+                    thread::sleep(Duration::from_millis(1));
+                }
+            });
+        }
+        let mut current = auto_splitter.write();
+        swapped.store(true, Ordering::Relaxed);
+        // Neither auto splitter can tick while the lock is held, so they
+        // can't interfere with the preparations.
+        prepare();
+        *current = new_auto_splitter;
+    });
+}
+
+pub fn compile_file(runtime: &Runtime, path: &Path) -> Result<CompiledAutoSplitter, anyhow::Error> {
+    compile(runtime, &read_file(path)?)
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>, anyhow::Error> {
+    fs::read(path).context("Failed loading the auto splitter from the file system.")
+}
+
+fn compile(runtime: &Runtime, data: &[u8]) -> Result<CompiledAutoSplitter, anyhow::Error> {
+    runtime
+        .compile(data)
+        .context("Failed loading the auto splitter.")
+}
+
+pub fn build_runtime(optimize: bool) -> Runtime {
+    let mut config = Config::default();
+    config.debug_info = true;
+    config.optimize = optimize;
+    Runtime::new(config).unwrap()
+}
@@ -0,0 +1,398 @@
+use std::{fmt, time::Instant};
+
+use indexmap::IndexMap;
+use livesplit_auto_splitting::TimerState;
+
+#[derive(PartialEq)]
+pub enum GameTimeState {
+    NotInitialized,
+    Running,
+    Paused,
+}
+
+impl fmt::Display for GameTimeState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameTimeState::NotInitialized => write!(f, "Not Initialized"),
+            GameTimeState::Running => write!(f, "Running"),
+            GameTimeState::Paused => write!(f, "Paused"),
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum TimerEvent {
+    Started,
+    Splitted,
+    SplitSkipped,
+    SplitUndone,
+    Reset,
+    Paused,
+    Resumed,
+    PausesUndone,
+}
+
+impl fmt::Display for TimerEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimerEvent::Started => write!(f, "Timer started."),
+            TimerEvent::Splitted => write!(f, "Splitted."),
+            TimerEvent::SplitSkipped => write!(f, "Split skipped."),
+            TimerEvent::SplitUndone => write!(f, "Split undone."),
+            TimerEvent::Reset => write!(f, "Timer reset."),
+            TimerEvent::Paused => write!(f, "Timer paused."),
+            TimerEvent::Resumed => write!(f, "Timer resumed."),
+            TimerEvent::PausesUndone => write!(f, "All pauses undone."),
+        }
+    }
+}
+
+/// The times at which a segment got splitted or skipped.
+pub struct SplitRecord {
+    pub splitted: bool,
+    pub real_time: time::Duration,
+    /// The game time is only recorded if the auto splitter initialized it.
+    pub game_time: Option<time::Duration>,
+}
+
+/// The state of the timer that the auto splitter controls. This is
+/// independent of the UI, so the GUI and the headless runner can share the
+/// same timer logic.
+pub struct TimerModel {
+    pub state: TimerState,
+    pub split_index: usize,
+    pub splits: Vec<SplitRecord>,
+    /// The wall clock time at which the current attempt was started.
+    pub attempt_started: Option<time::OffsetDateTime>,
+    start_instant: Option<Instant>,
+    paused_at: Option<Instant>,
+    pause_time: std::time::Duration,
+    pub game_time: time::Duration,
+    pub game_time_state: GameTimeState,
+    pub variables: IndexMap<String, String>,
+    /// The names of the segments. If there are none, the run never ends, so
+    /// the auto splitter can split as often as it wants to.
+    pub segments: Vec<String>,
+}
+
+impl TimerModel {
+    pub fn new() -> Self {
+        Self {
+            state: TimerState::NotRunning,
+            split_index: 0,
+            splits: Vec::new(),
+            attempt_started: None,
+            start_instant: None,
+            paused_at: None,
+            pause_time: std::time::Duration::ZERO,
+            game_time: time::Duration::ZERO,
+            game_time_state: GameTimeState::NotInitialized,
+            variables: IndexMap::new(),
+            segments: Vec::new(),
+        }
+    }
+
+    fn is_last_segment(&self) -> bool {
+        !self.segments.is_empty() && self.split_index + 1 >= self.segments.len()
+    }
+
+    /// The time since the attempt was started, excluding the time the timer
+    /// was paused. Once the run ended, this is the final time.
+    pub fn real_time(&self) -> time::Duration {
+        if self.state == TimerState::Ended {
+            if let Some(split) = self.splits.last() {
+                return split.real_time;
+            }
+        }
+        let Some(start_instant) = self.start_instant else {
+            return time::Duration::ZERO;
+        };
+        let now = self.paused_at.unwrap_or_else(Instant::now);
+        let elapsed = now.saturating_duration_since(start_instant);
+        elapsed
+            .saturating_sub(self.pause_time)
+            .try_into()
+            .unwrap_or(time::Duration::ZERO)
+    }
+
+    fn record_split(&mut self, splitted: bool) {
+        let game_time =
+            (self.game_time_state != GameTimeState::NotInitialized).then_some(self.game_time);
+        self.splits.push(SplitRecord {
+            splitted,
+            real_time: self.real_time(),
+            game_time,
+        });
+        self.split_index += 1;
+    }
+
+    pub fn start(&mut self) -> Option<TimerEvent> {
+        if self.state != TimerState::NotRunning {
+            return None;
+        }
+        self.state = TimerState::Running;
+        self.attempt_started = Some(time::OffsetDateTime::now_utc());
+        self.start_instant = Some(Instant::now());
+        self.paused_at = None;
+        self.pause_time = std::time::Duration::ZERO;
+        Some(TimerEvent::Started)
+    }
+
+    pub fn split(&mut self) -> Option<TimerEvent> {
+        if self.state != TimerState::Running {
+            return None;
+        }
+        let is_last_segment = self.is_last_segment();
+        self.record_split(true);
+        if is_last_segment {
+            self.state = TimerState::Ended;
+        }
+        Some(TimerEvent::Splitted)
+    }
+
+    pub fn skip_split(&mut self) -> Option<TimerEvent> {
+        // Just like in LiveSplit, the final split can't be skipped, as that
+        // would end the run without a final time.
+        if self.state != TimerState::Running || self.is_last_segment() {
+            return None;
+        }
+        self.record_split(false);
+        Some(TimerEvent::SplitSkipped)
+    }
+
+    pub fn undo_split(&mut self) -> Option<TimerEvent> {
+        if !matches!(self.state, TimerState::Running | TimerState::Ended) || self.splits.is_empty()
+        {
+            return None;
+        }
+        self.state = TimerState::Running;
+        self.splits.pop();
+        self.split_index = self.split_index.saturating_sub(1);
+        Some(TimerEvent::SplitUndone)
+    }
+
+    pub fn reset(&mut self) -> Option<TimerEvent> {
+        if self.state == TimerState::NotRunning {
+            return None;
+        }
+        self.state = TimerState::NotRunning;
+        self.split_index = 0;
+        self.splits.clear();
+        self.attempt_started = None;
+        self.start_instant = None;
+        self.paused_at = None;
+        self.pause_time = std::time::Duration::ZERO;
+        self.game_time = time::Duration::ZERO;
+        self.game_time_state = GameTimeState::NotInitialized;
+        self.variables.clear();
+        Some(TimerEvent::Reset)
+    }
+
+    pub fn pause(&mut self) -> Option<TimerEvent> {
+        if self.state != TimerState::Running {
+            return None;
+        }
+        self.state = TimerState::Paused;
+        self.paused_at = Some(Instant::now());
+        Some(TimerEvent::Paused)
+    }
+
+    pub fn resume(&mut self) -> Option<TimerEvent> {
+        if self.state != TimerState::Paused {
+            return None;
+        }
+        self.state = TimerState::Running;
+        if let Some(paused_at) = self.paused_at.take() {
+            self.pause_time += paused_at.elapsed();
+        }
+        Some(TimerEvent::Resumed)
+    }
+
+    /// Removes all the pauses from the real time, as if the timer was never
+    /// paused. If the timer is currently paused, it continues running.
+    pub fn undo_all_pauses(&mut self) -> Option<TimerEvent> {
+        if !matches!(self.state, TimerState::Running | TimerState::Paused) {
+            return None;
+        }
+        self.state = TimerState::Running;
+        self.paused_at = None;
+        self.pause_time = std::time::Duration::ZERO;
+        Some(TimerEvent::PausesUndone)
+    }
+
+    pub fn set_game_time(&mut self, time: time::Duration) {
+        self.game_time = time;
+        self.game_time_state = GameTimeState::Running;
+    }
+
+    pub fn pause_game_time(&mut self) -> bool {
+        if self.game_time_state == GameTimeState::Paused {
+            return false;
+        }
+        self.game_time_state = GameTimeState::Paused;
+        true
+    }
+
+    pub fn resume_game_time(&mut self) -> bool {
+        if self.game_time_state == GameTimeState::Running {
+            return false;
+        }
+        self.game_time_state = GameTimeState::Running;
+        true
+    }
+
+    pub fn is_variable(&self, key: &str, value: &str) -> bool {
+        self.variables.get(key).is_some_and(|v| v == value)
+    }
+
+    pub fn set_variable(&mut self, key: &str, value: &str) -> bool {
+        if self.is_variable(key, value) {
+            return false;
+        }
+        self.variables.insert(key.to_string(), value.to_string());
+        true
+    }
+
+    pub fn current_split_index(&self) -> Option<usize> {
+        Some(self.split_index)
+    }
+
+    pub fn segment_splitted(&self, index: usize) -> Option<bool> {
+        self.splits.get(index).map(|split| split.splitted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_segments(count: usize) -> TimerModel {
+        let mut model = TimerModel::new();
+        model.segments = (1..=count).map(|i| format!("Segment {i}")).collect();
+        model
+    }
+
+    #[test]
+    fn run_ends_with_the_last_split() {
+        let mut model = with_segments(2);
+        assert!(model.split().is_none());
+        assert!(model.start() == Some(TimerEvent::Started));
+        assert!(model.start().is_none());
+
+        assert!(model.split() == Some(TimerEvent::Splitted));
+        assert_eq!(model.state, TimerState::Running);
+        assert!(model.split() == Some(TimerEvent::Splitted));
+        assert_eq!(model.state, TimerState::Ended);
+        assert!(model.split().is_none());
+        assert_eq!(model.splits.len(), 2);
+        assert_eq!(model.segment_splitted(1), Some(true));
+    }
+
+    #[test]
+    fn run_without_segments_never_ends() {
+        let mut model = TimerModel::new();
+        model.start();
+        for _ in 0..10 {
+            assert!(model.split().is_some());
+        }
+        assert_eq!(model.state, TimerState::Running);
+        assert_eq!(model.current_split_index(), Some(10));
+    }
+
+    #[test]
+    fn last_split_cant_be_skipped() {
+        let mut model = with_segments(2);
+        model.start();
+        assert!(model.skip_split() == Some(TimerEvent::SplitSkipped));
+        assert_eq!(model.segment_splitted(0), Some(false));
+        assert!(model.skip_split().is_none());
+        assert_eq!(model.split_index, 1);
+    }
+
+    #[test]
+    fn undoing_the_last_split_continues_the_run() {
+        let mut model = with_segments(1);
+        assert!(model.undo_split().is_none());
+        model.start();
+        assert!(model.undo_split().is_none());
+        model.split();
+        assert_eq!(model.state, TimerState::Ended);
+
+        assert!(model.undo_split() == Some(TimerEvent::SplitUndone));
+        assert_eq!(model.state, TimerState::Running);
+        assert_eq!(model.split_index, 0);
+        assert!(model.splits.is_empty());
+        assert_eq!(model.segment_splitted(0), None);
+    }
+
+    #[test]
+    fn reset_clears_the_attempt() {
+        let mut model = with_segments(3);
+        assert!(model.reset().is_none());
+        model.start();
+        model.split();
+        model.set_game_time(time::Duration::seconds(5));
+        model.set_variable("level", "2");
+
+        assert!(model.reset() == Some(TimerEvent::Reset));
+        assert_eq!(model.state, TimerState::NotRunning);
+        assert_eq!(model.split_index, 0);
+        assert!(model.splits.is_empty());
+        assert!(model.attempt_started.is_none());
+        assert_eq!(model.game_time, time::Duration::ZERO);
+        assert!(model.game_time_state == GameTimeState::NotInitialized);
+        assert!(model.variables.is_empty());
+        assert_eq!(model.real_time(), time::Duration::ZERO);
+        assert_eq!(model.segments.len(), 3);
+    }
+
+    #[test]
+    fn pausing_stops_the_real_time() {
+        let mut model = TimerModel::new();
+        assert!(model.pause().is_none());
+        model.start();
+        assert!(model.resume().is_none());
+        assert!(model.pause() == Some(TimerEvent::Paused));
+        assert!(model.pause().is_none());
+        let paused_time = model.real_time();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        assert_eq!(model.real_time(), paused_time);
+
+        assert!(model.resume() == Some(TimerEvent::Resumed));
+        assert_eq!(model.state, TimerState::Running);
+        assert!(model.real_time() < time::Duration::milliseconds(5) + paused_time);
+
+        model.pause();
+        assert!(model.undo_all_pauses() == Some(TimerEvent::PausesUndone));
+        assert_eq!(model.state, TimerState::Running);
+        assert!(model.real_time() >= time::Duration::milliseconds(5));
+    }
+
+    #[test]
+    fn splits_record_the_game_time_once_initialized() {
+        let mut model = TimerModel::new();
+        model.start();
+        model.split();
+        assert_eq!(model.splits[0].game_time, None);
+
+        model.set_game_time(time::Duration::seconds(3));
+        assert!(model.pause_game_time());
+        assert!(!model.pause_game_time());
+        model.split();
+        assert_eq!(model.splits[1].game_time, Some(time::Duration::seconds(3)));
+
+        assert!(model.resume_game_time());
+        assert!(!model.resume_game_time());
+    }
+
+    #[test]
+    fn variables_only_change_with_a_new_value() {
+        let mut model = TimerModel::new();
+        assert!(model.set_variable("level", "1"));
+        assert!(!model.set_variable("level", "1"));
+        assert!(model.is_variable("level", "1"));
+        assert!(model.set_variable("level", "2"));
+        assert!(!model.is_variable("level", "1"));
+        assert!(!model.is_variable("deaths", "0"));
+    }
+}
@@ -0,0 +1,224 @@
+//! Records the timings of every tick, so they can be exported in the Chrome
+//! Trace Event format and looked at in a trace viewer like Perfetto.
+
+use std::{
+    collections::VecDeque,
+    io::{self, Write},
+    mem,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use serde_json::json;
+
+use crate::{runtime_thread::TickInfo, TimerEventKind, TimerEventSource};
+
+/// A tick takes up 56 bytes, so this is less than 60 MB worth of ticks, which
+/// covers a few hours at the usual tick rates.
+const TICK_CAPACITY: usize = 1_000_000;
+
+/// The auto splitter may call some of the timer functions on every tick, so
+/// their number is limited separately.
+const EVENT_CAPACITY: usize = 1_000_000;
+
+/// The records are stored in chunks of this many, so a snapshot of the trace
+/// only needs to copy the last chunk.
+const CHUNK_LEN: usize = 4096;
+
+const PID: u32 = 1;
+const TID: u32 = 1;
+
+#[derive(Clone)]
+struct TickRecord {
+    tick: u64,
+    start: Duration,
+    duration: Duration,
+    memory_usage: usize,
+    handles: u64,
+}
+
+#[derive(Clone)]
+struct EventRecord {
+    tick: u64,
+    time: Duration,
+    name: String,
+    source: TimerEventSource,
+    applied: bool,
+}
+
+/// Keeps the most recent records. Once the capacity is exceeded, the oldest
+/// chunk of records is dropped.
+#[derive(Clone)]
+struct Chunks<T> {
+    full: VecDeque<Arc<[T]>>,
+    current: Vec<T>,
+    capacity: usize,
+}
+
+impl<T> Chunks<T> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            full: VecDeque::new(),
+            current: Vec::new(),
+            capacity,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.full.is_empty() && self.current.is_empty()
+    }
+
+    fn push(&mut self, record: T) {
+        self.current.push(record);
+        if self.current.len() == CHUNK_LEN {
+            self.full.push_back(mem::take(&mut self.current).into());
+            if self.full.len() * CHUNK_LEN > self.capacity {
+                self.full.pop_front();
+            }
+        }
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &T> {
+        self.full
+            .iter()
+            .flat_map(|chunk| chunk.iter())
+            .chain(&self.current)
+    }
+}
+
+/// The most recent ticks and the timer events that happened during them. All
+/// times are relative to when the recording started. Cloning it is cheap, as
+/// the records are shared.
+#[derive(Clone)]
+pub struct TickTrace {
+    origin: Instant,
+    ticks: Chunks<TickRecord>,
+    events: Chunks<EventRecord>,
+}
+
+impl TickTrace {
+    pub fn new() -> Self {
+        Self {
+            origin: Instant::now(),
+            ticks: Chunks::new(TICK_CAPACITY),
+            events: Chunks::new(EVENT_CAPACITY),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ticks.is_empty()
+    }
+
+    pub fn record_tick(&mut self, tick: u64, info: &TickInfo) {
+        self.ticks.push(TickRecord {
+            tick,
+            start: info.started.saturating_duration_since(self.origin),
+            duration: info.time_of_tick,
+            memory_usage: info.memory_usage,
+            handles: info.handles,
+        });
+    }
+
+    pub fn record_event(
+        &mut self,
+        tick: u64,
+        kind: &TimerEventKind,
+        source: TimerEventSource,
+        applied: bool,
+    ) {
+        self.events.push(EventRecord {
+            tick,
+            time: self.origin.elapsed(),
+            name: kind.to_string(),
+            source,
+            applied,
+        });
+    }
+
+    /// Writes the trace as a JSON object in the Chrome Trace Event format. Each
+    /// tick is a complete event, the timer events are instant events within
+    /// them and the memory usage and handles are counters.
+    pub fn export(&self, mut writer: impl Write) -> io::Result<()> {
+        writer.write_all(b"{\"displayTimeUnit\":\"ms\",\"traceEvents\":[\n")?;
+
+        let metadata = [
+            json!({
+                "name": "process_name",
+                "ph": "M",
+                "pid": PID,
+                "args": { "name": "Auto Splitter" },
+            }),
+            json!({
+                "name": "thread_name",
+                "ph": "M",
+                "pid": PID,
+                "tid": TID,
+                "args": { "name": "Runtime" },
+            }),
+        ];
+        let ticks = self.ticks.iter().flat_map(|tick| {
+            let ts = micros(tick.start);
+            [
+                json!({
+                    "name": "update",
+                    "cat": "tick",
+                    "ph": "X",
+                    "ts": ts,
+                    "dur": micros(tick.duration),
+                    "pid": PID,
+                    "tid": TID,
+                    "args": {
+                        "tick": tick.tick,
+                        "memory": tick.memory_usage,
+                        "handles": tick.handles,
+                    },
+                }),
+                json!({
+                    "name": "Memory",
+                    "ph": "C",
+                    "ts": ts,
+                    "pid": PID,
+                    "args": { "bytes": tick.memory_usage },
+                }),
+                json!({
+                    "name": "Handles",
+                    "ph": "C",
+                    "ts": ts,
+                    "pid": PID,
+                    "args": { "handles": tick.handles },
+                }),
+            ]
+        });
+        let events = self.events.iter().map(|event| {
+            json!({
+                "name": event.name,
+                "cat": if event.applied { "timer" } else { "timer,ignored" },
+                "ph": "i",
+                "s": "t",
+                "ts": micros(event.time),
+                "pid": PID,
+                "tid": TID,
+                "args": {
+                    "tick": event.tick,
+                    "source": event.source.to_string(),
+                    "applied": event.applied,
+                },
+            })
+        });
+
+        for (i, event) in metadata.into_iter().chain(ticks).chain(events).enumerate() {
+            if i != 0 {
+                writer.write_all(b",\n")?;
+            }
+            serde_json::to_writer(&mut writer, &event)?;
+        }
+
+        writer.write_all(b"\n]}\n")?;
+        writer.flush()
+    }
+}
+
+/// The trace format measures time in microseconds.
+fn micros(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1_000_000.0
+}
@@ -1,10 +1,30 @@
+use std::path::PathBuf;
+
 use dioxus::prelude::*;
 
-use super::{DividerState, LogEntries, Logs};
+use super::{
+    DividerState, HorizonalDivider, LogEntries, Logs, MemoryInspector, MemoryView, SourceLocation,
+    SourceViewer, Timeline, TimerEvents,
+};
+
+#[derive(Copy, Clone, PartialEq)]
+enum Tab {
+    Logs,
+    Timeline,
+    Memory,
+}
 
 #[component]
-pub fn CentralPanel(logs: SyncSignal<LogEntries>, bottom_divider: Signal<DividerState>) -> Element {
-    let show_editor = use_signal(|| false);
+pub fn CentralPanel(
+    logs: SyncSignal<LogEntries>,
+    timer_events: SyncSignal<TimerEvents>,
+    source_files: SyncSignal<Vec<PathBuf>>,
+    memory_inspector: SyncSignal<MemoryInspector>,
+    bottom_divider: Signal<DividerState>,
+) -> Element {
+    let mut show_editor = use_signal(|| false);
+    let mut location = use_signal(|| None::<SourceLocation>);
+    let mut tab = use_signal(|| Tab::Logs);
     rsx! {
         div {
             class: "bar",
@@ -13,10 +33,66 @@ pub fn CentralPanel(logs: SyncSignal<LogEntries>, bottom_divider: Signal<Divider
             display: "flex",
             flex_direction: "column",
             gap: "10px",
-            Logs {
-                logs,
-                show_editor,
-                height: bottom_divider.read().size,
+            if show_editor() {
+                SourceViewer {
+                    source_files,
+                    location,
+                    onclose: move |_| show_editor.set(false),
+                }
+                HorizonalDivider { state: bottom_divider }
+            }
+            div {
+                flex_grow: if *show_editor.read() { "0" } else { "1" },
+                flex_shrink: "0",
+                height: if *show_editor.read() { "{bottom_divider.read().size}px" },
+                display: "flex",
+                flex_direction: "column",
+                gap: "5px",
+                overflow: "hidden",
+                div { class: "tabs",
+                    for (t , name) in [(Tab::Logs, "Logs"), (Tab::Timeline, "Timeline"), (Tab::Memory, "Memory")] {
+                        button {
+                            class: if tab() == t { "tab active" } else { "tab" },
+                            onclick: move |_| tab.set(t),
+                            "{name}"
+                        }
+                    }
+                    button {
+                        class: if show_editor() { "tab active" } else { "tab" },
+                        title: "Show the source files of the auto splitter",
+                        onclick: move |_| show_editor.toggle(),
+                        "Source"
+                    }
+                }
+                match tab() {
+                    Tab::Logs => rsx! {
+                        Logs {
+                            logs,
+                            onlocation: move |mut new_location: SourceLocation| {
+                                // Panics only mention the path relative to the
+                                // crate, so they are matched with the paths
+                                // from the debug information.
+                                if new_location.path.is_relative() {
+                                    if let Some(path) = source_files
+                                        .read()
+                                        .iter()
+                                        .find(|path| path.ends_with(&new_location.path))
+                                    {
+                                        new_location.path = path.clone();
+                                    }
+                                }
+                                location.set(Some(new_location));
+                                show_editor.set(true);
+                            },
+                        }
+                    },
+                    Tab::Timeline => rsx! {
+                        Timeline { timer_events }
+                    },
+                    Tab::Memory => rsx! {
+                        MemoryView { inspector: memory_inspector }
+                    },
+                }
             }
         }
     }
@@ -1,21 +1,23 @@
 use dioxus::prelude::*;
-use livesplit_auto_splitting::{AutoSplitter, CompiledAutoSplitter, Runtime, TimerState};
+use livesplit_auto_splitting::{AutoSplitter, CompiledAutoSplitter, Runtime};
 
-use crate::{GameTimeState, IdeTimer};
+use crate::{module_info::ModuleInfo, trace::TickTrace, IdeTimer, TimerModel};
 
-use super::{AutoSplitterControl, SideBar, Statistics, StatisticsData, TimerInfo};
+use super::{
+    AutoSplitterControl, LogEntries, ModuleInfoView, SideBar, Statistics, StatisticsData, TimerInfo,
+};
 
 #[component]
 pub fn LeftSideBar(
     timer: SyncSignal<IdeTimer>,
-    split_index: SyncSignal<usize>,
-    timer_state: SyncSignal<TimerState>,
-    game_time: SyncSignal<time::Duration>,
-    game_time_state: SyncSignal<GameTimeState>,
+    timer_model: SyncSignal<TimerModel>,
     runtime: SyncSignal<Runtime>,
     module: SyncSignal<Option<CompiledAutoSplitter>>,
     auto_splitter: SyncSignal<Option<AutoSplitter<IdeTimer>>>,
     statistics: SyncSignal<StatisticsData>,
+    trace: SyncSignal<TickTrace>,
+    logs: SyncSignal<LogEntries>,
+    module_info: SyncSignal<Option<ModuleInfo>>,
     optimize: Signal<bool>,
     width: f64,
 ) -> Element {
@@ -28,14 +30,9 @@ pub fn LeftSideBar(
                 auto_splitter,
                 optimize,
             }
-            TimerInfo {
-                split_index,
-                timer_state,
-                game_time,
-                game_time_state,
-                timer,
-            }
-            Statistics { statistics }
+            ModuleInfoView { module_info }
+            TimerInfo { timer_model, timer }
+            Statistics { statistics, trace, logs }
         }
     }
 }
@@ -4,6 +4,8 @@ mod left_side_bar;
 mod panel;
 mod right_side_bar;
 mod side_bar;
+mod source_viewer;
+mod sparkline;
 pub mod swapy;
 mod toggle;
 mod variables;
@@ -17,6 +19,8 @@ pub use left_side_bar::*;
 pub use panel::*;
 pub use right_side_bar::*;
 pub use side_bar::*;
+pub use source_viewer::*;
+pub use sparkline::*;
 pub use toggle::*;
 pub use variables::*;
 pub use widgets::*;
@@ -77,3 +81,15 @@ impl fmt::Display for FmtTime {
         write!(f, "{:02}:{:02}:{:02}", h, m, s)
     }
 }
+
+struct FmtBytes(u64);
+
+impl fmt::Display for FmtBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:.2}",
+            byte_unit::Byte::from_u64(self.0).get_appropriate_unit(byte_unit::UnitType::Binary)
+        )
+    }
+}
@@ -1,26 +1,41 @@
+use std::path::PathBuf;
+
 use dioxus::prelude::*;
-use indexmap::IndexMap;
 use livesplit_auto_splitting::{settings, AutoSplitter};
 
-use crate::{IdeTimer, Widgets};
+use crate::{IdeTimer, TimerModel, Widgets};
 
-use super::{Processes, SettingsGui, SettingsMap, SideBar, Variables};
+use super::{
+    FakeGameControl, LogEntries, Processes, SettingsGui, SettingsMap, SideBar, VariableHistory,
+    Variables,
+};
 
 #[component]
 pub fn RightSideBar(
     auto_splitter: SyncSignal<Option<AutoSplitter<IdeTimer>>>,
-    variables: SyncSignal<IndexMap<String, String>>,
+    timer_model: SyncSignal<TimerModel>,
+    variable_history: SyncSignal<VariableHistory>,
     processes: SyncSignal<Vec<(String, String)>>,
     settings_widgets: SyncSignal<Widgets>,
     settings_map: SyncSignal<settings::Map>,
+    settings_restored: SyncSignal<bool>,
+    wasm_path: SyncSignal<Option<PathBuf>>,
+    logs: SyncSignal<LogEntries>,
     width: f64,
 ) -> Element {
     rsx! {
         SideBar { width,
-            Variables { variables }
+            Variables { timer_model, variable_history }
             Processes { processes }
+            FakeGameControl { logs }
             SettingsGui { settings_widgets, settings_map, auto_splitter }
-            SettingsMap { settings_map, auto_splitter }
+            SettingsMap {
+                settings_map,
+                settings_restored,
+                wasm_path,
+                auto_splitter,
+                logs,
+            }
         }
     }
 }
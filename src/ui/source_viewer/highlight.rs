@@ -0,0 +1,256 @@
+//! A simple syntax highlighter for the languages auto splitters are usually
+//! written in. It only distinguishes keywords, types, literals and comments,
+//! which is enough to make the code easy to read.
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum TokenKind {
+    Plain,
+    Keyword,
+    Type,
+    String,
+    Number,
+    Comment,
+}
+
+impl TokenKind {
+    pub fn class(self) -> &'static str {
+        match self {
+            TokenKind::Plain => "",
+            TokenKind::Keyword => "token-keyword",
+            TokenKind::Type => "token-type",
+            TokenKind::String => "token-string",
+            TokenKind::Number => "token-number",
+            TokenKind::Comment => "token-comment",
+        }
+    }
+}
+
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while",
+];
+
+const C_KEYWORDS: &[&str] = &[
+    "auto",
+    "bool",
+    "break",
+    "case",
+    "char",
+    "class",
+    "const",
+    "continue",
+    "default",
+    "define",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "extern",
+    "false",
+    "float",
+    "for",
+    "goto",
+    "if",
+    "include",
+    "inline",
+    "int",
+    "long",
+    "namespace",
+    "nullptr",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "short",
+    "signed",
+    "sizeof",
+    "static",
+    "struct",
+    "switch",
+    "template",
+    "true",
+    "typedef",
+    "typename",
+    "union",
+    "unsigned",
+    "virtual",
+    "void",
+    "volatile",
+    "while",
+];
+
+const ZIG_KEYWORDS: &[&str] = &[
+    "and",
+    "break",
+    "comptime",
+    "const",
+    "continue",
+    "defer",
+    "else",
+    "enum",
+    "errdefer",
+    "error",
+    "export",
+    "extern",
+    "false",
+    "fn",
+    "for",
+    "if",
+    "inline",
+    "null",
+    "or",
+    "orelse",
+    "pub",
+    "return",
+    "struct",
+    "switch",
+    "true",
+    "try",
+    "undefined",
+    "union",
+    "var",
+    "while",
+];
+
+/// Determines the keywords of the language by the extension of the file.
+pub fn keywords(extension: &str) -> &'static [&'static str] {
+    match extension {
+        "rs" => RUST_KEYWORDS,
+        "c" | "h" | "cc" | "cpp" | "cxx" | "hpp" | "hh" => C_KEYWORDS,
+        "zig" => ZIG_KEYWORDS,
+        _ => &[],
+    }
+}
+
+/// Splits the source into lines of highlighted tokens.
+pub fn highlight<'a>(source: &'a str, keywords: &[&str]) -> Vec<Vec<(TokenKind, &'a str)>> {
+    let mut lines = vec![Vec::new()];
+    for (kind, token) in tokenize(source, keywords) {
+        let mut parts = token.split('\n');
+        if let Some(first) = parts.next() {
+            push_token(lines.last_mut().unwrap(), kind, first);
+        }
+        for part in parts {
+            let mut line = Vec::new();
+            push_token(&mut line, kind, part);
+            lines.push(line);
+        }
+    }
+    lines
+}
+
+fn push_token<'a>(line: &mut Vec<(TokenKind, &'a str)>, kind: TokenKind, text: &'a str) {
+    let text = text.strip_suffix('\r').unwrap_or(text);
+    if !text.is_empty() {
+        line.push((kind, text));
+    }
+}
+
+fn tokenize<'a>(source: &'a str, keywords: &[&str]) -> Vec<(TokenKind, &'a str)> {
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let mut plain_start = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let start = i;
+        let kind = match bytes[i] {
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                i = find_from(source, i, "\n").unwrap_or(bytes.len());
+                TokenKind::Comment
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = find_from(source, i + 2, "*/").map_or(bytes.len(), |end| end + 2);
+                TokenKind::Comment
+            }
+            b'"' => {
+                i = string_end(bytes, i + 1, b'"');
+                TokenKind::String
+            }
+            // Rust uses single quotes for lifetimes as well, so only complete
+            // character literals are highlighted.
+            b'\'' if is_char_literal(bytes, i) => {
+                i = string_end(bytes, i + 1, b'\'');
+                TokenKind::String
+            }
+            b'0'..=b'9' => {
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                    i += 1;
+                }
+                // Fractional part
+                if bytes.get(i) == Some(&b'.') && bytes.get(i + 1).is_some_and(u8::is_ascii_digit) {
+                    i += 1;
+                    while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_')
+                    {
+                        i += 1;
+                    }
+                }
+                TokenKind::Number
+            }
+            b if b.is_ascii_alphabetic() || b == b'_' => {
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                    i += 1;
+                }
+                let word = &source[start..i];
+                if keywords.contains(&word) {
+                    TokenKind::Keyword
+                } else if !keywords.is_empty() && word.starts_with(|c: char| c.is_ascii_uppercase())
+                {
+                    TokenKind::Type
+                } else {
+                    continue;
+                }
+            }
+            _ => {
+                i += source[i..].chars().next().map_or(1, char::len_utf8);
+                continue;
+            }
+        };
+
+        if plain_start < start {
+            tokens.push((TokenKind::Plain, &source[plain_start..start]));
+        }
+        tokens.push((kind, &source[start..i]));
+        plain_start = i;
+    }
+
+    if plain_start < bytes.len() {
+        tokens.push((TokenKind::Plain, &source[plain_start..]));
+    }
+    tokens
+}
+
+fn find_from(source: &str, from: usize, pattern: &str) -> Option<usize> {
+    source[from..].find(pattern).map(|index| from + index)
+}
+
+/// Returns the index after the closing quote, skipping escaped characters.
+fn string_end(bytes: &[u8], mut i: usize, quote: u8) -> usize {
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b if b == quote => return i + 1,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+fn is_char_literal(bytes: &[u8], i: usize) -> bool {
+    match bytes.get(i + 1) {
+        Some(b'\\') => true,
+        Some(_) => {
+            // The character itself may be multiple bytes long.
+            let rest = &bytes[i + 1..];
+            let len = rest
+                .iter()
+                .skip(1)
+                .position(|&b| b & 0xC0 != 0x80)
+                .map_or(rest.len(), |p| p + 1);
+            rest.get(len) == Some(&b'\'')
+        }
+        None => false,
+    }
+}
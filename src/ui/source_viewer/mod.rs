@@ -0,0 +1,177 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use dioxus::prelude::*;
+use dioxus_material_icons::MaterialIcon;
+
+use super::Panel;
+
+mod highlight;
+
+/// A location in a source file that the source viewer can jump to.
+#[derive(Clone, PartialEq)]
+pub struct SourceLocation {
+    pub path: PathBuf,
+    pub line: Option<usize>,
+}
+
+#[component]
+pub fn SourceViewer(
+    source_files: SyncSignal<Vec<PathBuf>>,
+    mut location: Signal<Option<SourceLocation>>,
+    onclose: EventHandler,
+) -> Element {
+    // Files that don't exist on this machine, like the ones of the standard
+    // library, are listed after the ones that can be opened.
+    let files = use_memo(move || {
+        let mut files = source_files
+            .read()
+            .iter()
+            .map(|path| (path.clone(), path.is_file()))
+            .collect::<Vec<_>>();
+        files.sort_by_key(|(_, exists)| !exists);
+        files
+    });
+
+    let current_path = use_memo(move || location.read().as_ref().map(|l| l.path.clone()));
+    let source = use_memo(move || {
+        let path = current_path.read();
+        let path = path.as_ref()?;
+        Some(fs::read_to_string(path).map_err(|e| e.to_string()))
+    });
+
+    let current_location = location.read();
+    let current_location = current_location.as_ref();
+
+    rsx! {
+        Panel {
+            class: "source-viewer",
+            flex_grow: "1",
+            min_height: "75px",
+            div { class: "source-files",
+                if files.read().is_empty() {
+                    div { class: "gray",
+                        "The auto splitter has no debug information that lists its source files."
+                    }
+                }
+                for (path , exists) in files.read().iter().cloned() {
+                    div {
+                        class: if current_location.is_some_and(|l| l.path == path) { "source-file active" } else if exists { "source-file" } else { "source-file gray" },
+                        title: "{path.display()}",
+                        onclick: move |_| {
+                            location
+                                .set(
+                                    Some(SourceLocation {
+                                        path: path.clone(),
+                                        line: None,
+                                    }),
+                                )
+                        },
+                        "{short_path(&path)}"
+                    }
+                }
+            }
+            div { class: "source-code",
+                match &*source.read() {
+                    None => rsx! {
+                        div { class: "gray", "Select a source file to view it." }
+                    },
+                    Some(Err(e)) => rsx! {
+                        div { class: "error", "{e}" }
+                    },
+                    Some(Ok(source)) => rsx! {
+                        SourceCode {
+                            // A new file starts with a new component, so the
+                            // lines of the previous one aren't scrolled to.
+                            key: "{current_location.map(|l| l.path.display().to_string()).unwrap_or_default()}",
+                            source: source.clone(),
+                            extension: current_location
+                                .and_then(|l| l.path.extension())
+                                .map(|ext| ext.to_string_lossy().to_lowercase())
+                                .unwrap_or_default(),
+                            location,
+                        }
+                    },
+                }
+            }
+            button {
+                class: "source-close",
+                title: "Close the source viewer",
+                onclick: move |_| onclose.call(()),
+                MaterialIcon { name: "close", size: 20 }
+            }
+        }
+    }
+}
+
+#[component]
+fn SourceCode(
+    source: String,
+    extension: String,
+    location: Signal<Option<SourceLocation>>,
+) -> Element {
+    let mut line_elements = use_signal(HashMap::<usize, Rc<MountedData>>::new);
+
+    // Jumping to a line of the file that is already open doesn't mount the
+    // line again, so it is scrolled to whenever the location is set.
+    use_effect(move || {
+        let line = location.read().as_ref().and_then(|l| l.line);
+        if let Some(element) = line.and_then(|line| line_elements.peek().get(&line).cloned()) {
+            spawn(scroll_to_line(element));
+        }
+    });
+
+    let line = location.read().as_ref().and_then(|l| l.line);
+    let lines = highlight::highlight(&source, highlight::keywords(&extension));
+    rsx! {
+        for (index , tokens) in lines.into_iter().enumerate() {
+            div {
+                class: if line == Some(index + 1) { "source-line current" } else { "source-line" },
+                onmounted: move |ev| {
+                    line_elements.write().insert(index + 1, ev.data());
+                    async move {
+                        if line == Some(index + 1) {
+                            scroll_to_line(ev.data()).await;
+                        }
+                    }
+                },
+                span { class: "line-number", "{index + 1}" }
+                span {
+                    for (kind , text) in tokens {
+                        span { class: kind.class(), "{text}" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+async fn scroll_to_line(element: Rc<MountedData>) {
+    let _ = element
+        .scroll_to_with_options(ScrollToOptions {
+            behavior: ScrollBehavior::Instant,
+            vertical: ScrollLogicalPosition::Center,
+            horizontal: ScrollLogicalPosition::Start,
+        })
+        .await;
+}
+
+/// Shortens the path to the file name and its parent directory, which is
+/// usually enough to tell the files apart.
+fn short_path(path: &Path) -> String {
+    let mut components = path.components().rev();
+    let file_name = components.next();
+    let parent = components.next();
+    match (parent, file_name) {
+        (Some(parent), Some(file_name)) => format!(
+            "{}/{}",
+            parent.as_os_str().to_string_lossy(),
+            file_name.as_os_str().to_string_lossy()
+        ),
+        _ => path.display().to_string(),
+    }
+}
@@ -0,0 +1,68 @@
+use std::fmt::Write;
+
+use dioxus::prelude::*;
+
+const SPARKLINE_WIDTH: f64 = 200.0;
+
+/// The smallest and the largest of the values.
+pub fn value_range(values: impl IntoIterator<Item = f64>) -> (f64, f64) {
+    values
+        .into_iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| {
+            (min.min(v), max.max(v))
+        })
+}
+
+/// Graphs the values over time, fitted to the range from `min` to `max`. The
+/// values are `(time, value)` pairs in order. With `steps`, each value is kept
+/// until the next one, instead of changing gradually.
+#[component]
+pub fn Sparkline(values: Vec<(f64, f64)>, min: f64, max: f64, height: f64, steps: bool) -> Element {
+    let points = sparkline_points(&values, min, max, height, steps);
+    rsx! {
+        svg {
+            width: "100%",
+            height: "{height}",
+            view_box: "0 0 {SPARKLINE_WIDTH} {height}",
+            preserve_aspect_ratio: "none",
+            polyline { class: "sparkline", points }
+        }
+    }
+}
+
+/// The labels at the start and the end of a sparkline.
+#[component]
+pub fn SparklineRange(start: String, end: String) -> Element {
+    rsx! {
+        div { class: "sparkline-range",
+            span { "{start}" }
+            span { "{end}" }
+        }
+    }
+}
+
+fn sparkline_points(values: &[(f64, f64)], min: f64, max: f64, height: f64, steps: bool) -> String {
+    let mut points = String::new();
+    let (Some(&(first_time, _)), Some(&(last_time, _))) = (values.first(), values.last()) else {
+        return points;
+    };
+    let time_range = if last_time > first_time {
+        last_time - first_time
+    } else {
+        1.0
+    };
+    let value_range = if max > min { max - min } else { 1.0 };
+
+    let x = |time: f64| (time - first_time) / time_range * SPARKLINE_WIDTH;
+    let y = |value: f64| height - (value - min) / value_range * height;
+
+    let mut previous = None;
+    for &(time, value) in values {
+        if let Some(previous) = previous.filter(|_| steps) {
+            let _ = write!(points, "{:.2},{:.2} ", x(time), y(previous));
+        }
+        let _ = write!(points, "{:.2},{:.2} ", x(time), y(value));
+        previous = Some(value);
+    }
+    points
+}
@@ -1,20 +1,135 @@
+use std::collections::VecDeque;
+
 use dioxus::prelude::*;
 use indexmap::IndexMap;
 
-use super::Widget;
+use crate::TimerModel;
+
+use super::{value_range, Sparkline, SparklineRange, Widget};
+
+/// The number of changes that are kept for each variable.
+const HISTORY_LEN: usize = 1000;
+
+/// The number of changes that are listed when a variable is expanded.
+const LIST_LEN: usize = 50;
+
+const SPARKLINE_HEIGHT: f64 = 40.0;
+
+/// The values that each variable had over time, along with the tick in which
+/// they got set.
+pub struct VariableHistory {
+    variables: IndexMap<String, VecDeque<(u64, String)>>,
+}
+
+impl VariableHistory {
+    pub fn new() -> Self {
+        Self {
+            variables: IndexMap::new(),
+        }
+    }
+
+    pub fn push(&mut self, tick: u64, key: &str, value: &str) {
+        let history = match self.variables.get_mut(key) {
+            Some(history) => history,
+            None => self.variables.entry(key.to_owned()).or_default(),
+        };
+        if history.len() == HISTORY_LEN {
+            history.pop_front();
+        }
+        history.push_back((tick, value.to_owned()));
+    }
+
+    pub fn clear(&mut self) {
+        self.variables.clear();
+    }
+}
 
 #[component]
-pub fn Variables(variables: SyncSignal<IndexMap<String, String>>) -> Element {
+pub fn Variables(
+    timer_model: SyncSignal<TimerModel>,
+    variable_history: SyncSignal<VariableHistory>,
+) -> Element {
+    let mut expanded = use_signal(Vec::<String>::new);
+
+    let variables = &timer_model.read().variables;
+    let history = &variable_history.read().variables;
+    let expanded_keys = &*expanded.read();
     rsx! {
         Widget { title: "Variables",
-            if !variables.read().is_empty() {
+            if !variables.is_empty() {
                 div { class: "table",
-                    for (key , value) in variables.read().iter() {
-                        div { "{key}" }
+                    for (key , value) in variables.iter() {
+                        div {
+                            cursor: "pointer",
+                            title: "Show the history of the variable.",
+                            onclick: {
+                                let key = key.clone();
+                                move |_| {
+                                    let expanded = &mut *expanded.write();
+                                    if let Some(index) = expanded.iter().position(|k| *k == key) {
+                                        expanded.remove(index);
+                                    } else {
+                                        expanded.push(key.clone());
+                                    }
+                                }
+                            },
+                            "{key}"
+                        }
                         div { "{value}" }
+                        if expanded_keys.contains(key) {
+                            if let Some(history) = history.get(key) {
+                                div { class: "variable-history",
+                                    {variable_changes(history)}
+                                }
+                                // Keeps the rows of the table alternating.
+                                div { display: "none" }
+                            }
+                        }
                     }
                 }
             }
         }
     }
 }
+
+/// The history is graphed if all the values are numbers. Values like `NaN`
+/// and `inf` can't be placed on the graph, so they are left out.
+fn variable_changes(history: &VecDeque<(u64, String)>) -> Element {
+    let numbers = history
+        .iter()
+        .map(|(tick, value)| {
+            let number = value.trim().parse::<f64>().ok()?;
+            Some(number.is_finite().then_some((*tick, number)))
+        })
+        .collect::<Option<Vec<_>>>()
+        .map(|numbers| numbers.into_iter().flatten().collect::<Vec<_>>())
+        .filter(|numbers| numbers.len() > 1);
+
+    if let Some(numbers) = numbers {
+        let (min, max) = value_range(numbers.iter().map(|&(_, v)| v));
+        let (first_tick, last_tick) = (numbers[0].0, numbers[numbers.len() - 1].0);
+
+        // The variable keeps its value until the next change, so the graph is
+        // drawn as steps.
+        rsx! {
+            SparklineRange { start: "{max}", end: "{min}" }
+            Sparkline {
+                values: numbers.iter().map(|&(tick, v)| (tick as f64, v)).collect::<Vec<_>>(),
+                min,
+                max,
+                height: SPARKLINE_HEIGHT,
+                steps: true,
+            }
+            SparklineRange { start: "Tick {first_tick}", end: "Tick {last_tick}" }
+        }
+    } else {
+        rsx! {
+            for (tick , value) in history.iter().rev().take(LIST_LEN) {
+                div { class: "variable-change",
+                    span { class: "gray", "{tick}" }
+                    span { "{value}" }
+                }
+            }
+        }
+    }
+}
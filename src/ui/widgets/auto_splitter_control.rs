@@ -1,11 +1,27 @@
+use std::path::PathBuf;
+
 use dioxus::{desktop::window, prelude::*};
-use livesplit_auto_splitting::{AutoSplitter, CompiledAutoSplitter, Runtime};
-use notify::{EventKind, RecursiveMode, Watcher};
+use livesplit_auto_splitting::{AutoSplitter, CompiledAutoSplitter, LogLevel, Runtime};
 
-use crate::{build_runtime, ui::Toggle, IdeTimer};
+use crate::{
+    build::{self, ProjectWatcher},
+    build_runtime, cli, debugger,
+    hot_reload::WasmWatcher,
+    ui::Toggle,
+    watchdog::{self, WATCHDOG},
+    IdeTimer, LoadBehavior, LoadOptions,
+};
 
 use super::Widget;
 
+type BehaviorAccessor = fn(&mut LoadOptions) -> &mut LoadBehavior;
+
+const LOAD_KINDS: [(&str, BehaviorAccessor); 3] = [
+    ("Load", |options| &mut options.load),
+    ("Reload", |options| &mut options.reload),
+    ("Restart", |options| &mut options.restart),
+];
+
 #[component]
 pub fn AutoSplitterControl(
     timer: SyncSignal<IdeTimer>,
@@ -25,58 +41,171 @@ pub fn AutoSplitterControl(
             return;
         };
 
-        timer
-            .read()
-            .load_file(file.path(), runtime, module, auto_splitter);
+        let timer = *timer.read();
+        timer.load_file(file.path(), None, runtime, module, auto_splitter);
     };
 
-    let mut wasm_path = timer.read().wasm_path;
+    let mut project = use_signal_sync(|| None::<PathBuf>);
+    let mut build_command = use_signal(|| {
+        cli::args()
+            .build_command
+            .clone()
+            .unwrap_or_else(|| build::DEFAULT_COMMAND.into())
+    });
+    let mut logs = timer.read().logs;
 
-    use_memo(move || {
-        struct NotEq<T>(T);
+    let open_project = move |_| async move {
+        let Some(dir) = rfd::AsyncFileDialog::new()
+            .set_parent(&window().window)
+            .pick_folder()
+            .await
+        else {
+            return;
+        };
+        if !dir.path().join("Cargo.toml").is_file() {
+            logs.write().push_level(
+                format!("{} is not a Cargo project.", dir.path().display()),
+                LogLevel::Error,
+            );
+            return;
+        }
+        project.set(Some(dir.path().to_path_buf()));
+    };
 
-        impl<T> PartialEq for NotEq<T> {
-            fn eq(&self, _: &Self) -> bool {
-                false
-            }
+    let wasm_path = timer.read().wasm_path;
+
+    struct NotEq<T>(T);
+
+    impl<T> PartialEq for NotEq<T> {
+        fn eq(&self, _: &Self) -> bool {
+            false
         }
+    }
 
-        let wasm_path = wasm_path.read();
-        let wasm_path = wasm_path.as_ref()?;
-
-        let mut watcher =
-            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
-                let Ok(event) = event else {
-                    return;
-                };
-                let should_reload = match event.kind {
-                    EventKind::Create(_) | EventKind::Modify(_) => true,
-                    _ => event.need_rescan(),
-                };
-                if should_reload {
-                    timer.read().reload(runtime, module, auto_splitter);
+    let project_watcher = use_memo(move || {
+        let dir = project.read().clone()?;
+        let watcher = ProjectWatcher::new(
+            dir,
+            build_command.read().clone(),
+            move |message, level| logs.write().push_level(message, level),
+            move |path| {
+                let timer = *timer.read();
+                if timer.wasm_path.peek().as_ref() == Some(&path) {
+                    timer.reload(runtime, module, auto_splitter);
+                } else {
+                    timer.load_file(&path, None, runtime, module, auto_splitter);
                 }
-            })
-            .ok()?;
-        watcher.watch(wasm_path, RecursiveMode::NonRecursive).ok()?;
-        Some(NotEq(watcher))
+            },
+        );
+        match watcher {
+            Ok(watcher) => Some(NotEq(watcher)),
+            Err(e) => {
+                logs.write().push_level(format!("{e:?}"), LogLevel::Error);
+                None
+            }
+        }
+    });
+
+    use_memo(move || {
+        // The project watcher takes care of reloading the auto splitter after
+        // it got built.
+        if project.read().is_some() {
+            return None;
+        }
+
+        let wasm_path = wasm_path.read().clone()?;
+        let watcher = WasmWatcher::new(
+            wasm_path,
+            move || {
+                let timer = *timer.read();
+                timer.reload(runtime, module, auto_splitter);
+            },
+            move |message, level| logs.write().push_level(message, level),
+        );
+        match watcher {
+            Ok(watcher) => Some(NotEq(watcher)),
+            Err(e) => {
+                logs.write().push_level(format!("{e:?}"), LogLevel::Error);
+                None
+            }
+        }
     });
 
+    let mut breakpoints = use_signal(|| String::from("update"));
+    let debug = move |_| {
+        if let Some(warning) = debugger::ptrace_warning() {
+            logs.write().push_level(warning, LogLevel::Warning);
+        }
+        let breakpoints = breakpoints.read();
+        let breakpoints = breakpoints
+            .split(',')
+            .map(str::trim)
+            .filter(|breakpoint| !breakpoint.is_empty())
+            .collect::<Vec<_>>();
+        match debugger::launch(&cli::args().debugger, &breakpoints) {
+            Ok(script) => logs.write().push_level(
+                format!(
+                    "Launched the debugger with the script {}.",
+                    script.display()
+                ),
+                LogLevel::Info,
+            ),
+            Err(e) => logs.write().push_level(format!("{e:?}"), LogLevel::Error),
+        }
+    };
+
     let has_auto_splitter = auto_splitter.read().is_some();
+    let mut load_options = timer.read().load_options;
+    let mut watchdog_limit =
+        use_signal(|| WATCHDOG.limit().map_or(0.0, |limit| limit.as_secs_f64()));
 
     rsx! {
         Widget { title: "Auto Splitter",
             button { onclick: open, "Open" }
-            if has_auto_splitter {
+            button {
+                title: "Open a Cargo project that gets rebuilt whenever its sources change",
+                onclick: open_project,
+                "Open Project"
+            }
+            if let Some(dir) = &*project.read() {
+                div { class: "project", title: "{dir.display()}",
+                    "Project: "
+                    span { class: "gray",
+                        "{dir.file_name().unwrap_or(dir.as_os_str()).to_string_lossy()}"
+                    }
+                }
+                input {
+                    class: "build-command",
+                    title: "The command that builds the project. It is split at whitespace.",
+                    value: "{build_command}",
+                    onkeydown: move |ev| ev.stop_propagation(),
+                    onchange: move |ev| build_command.set(ev.value()),
+                }
+                button {
+                    onclick: move |_| {
+                        if let Some(watcher) = &*project_watcher.read() {
+                            watcher.0.rebuild();
+                        }
+                    },
+                    "Build"
+                }
+            }
+            if has_auto_splitter || project.read().is_some() {
                 button {
                     onclick: move |_| {
-                        module.set(None);
-                        auto_splitter.set(None);
-                        wasm_path.set(None);
+                        project.set(None);
+                        let timer = *timer.read();
+                        timer.close(module, auto_splitter);
                     },
                     "Close"
                 }
-                button { onclick: move |_| timer.read().restart(runtime, module, auto_splitter),
+            }
+            if has_auto_splitter {
+                button {
+                    onclick: move |_| {
+                        let timer = *timer.read();
+                        timer.restart(runtime, module, auto_splitter);
+                    },
                     "Restart"
                 }
                 button {
@@ -95,11 +224,80 @@ pub fn AutoSplitterControl(
                         let should_optimize = event.checked();
                         optimize.set(should_optimize);
                         runtime.set(build_runtime(should_optimize));
-                        timer.read().reload(runtime, module, auto_splitter);
+                        let timer = *timer.read();
+                        timer.reload(runtime, module, auto_splitter);
                     },
                     "Optimize"
                 }
             }
+            div { class: "debugger",
+                button {
+                    title: "Attach a debugger to the IDE to step through the auto splitter's code",
+                    onclick: debug,
+                    "Debug"
+                }
+                input {
+                    title: "The breakpoints to set, separated by commas. Each is either a function name or a file:line location.",
+                    placeholder: "Breakpoints",
+                    value: "{breakpoints}",
+                    onkeydown: move |ev| ev.stop_propagation(),
+                    onchange: move |ev| breakpoints.set(ev.value()),
+                }
+            }
+            div { class: "load-options",
+                div {}
+                div { title: "Resets the timer, which also clears the variables",
+                    "Reset Timer"
+                }
+                div { "Clear Variables" }
+                for (name , behavior) in LOAD_KINDS {
+                    {
+                        let current = *behavior(&mut load_options());
+                        rsx! {
+                            div { "{name}" }
+                            input {
+                                r#type: "checkbox",
+                                checked: current.reset_timer,
+                                onchange: move |ev| {
+                                    behavior(&mut load_options.write()).reset_timer = ev.checked();
+                                },
+                            }
+                            input {
+                                r#type: "checkbox",
+                                checked: current.reset_timer || current.clear_variables,
+                                disabled: current.reset_timer,
+                                onchange: move |ev| {
+                                    behavior(&mut load_options.write()).clear_variables = ev.checked();
+                                },
+                            }
+                        }
+                    }
+                }
+            }
+            label {
+                class: "watchdog",
+                title: "Interrupts the auto splitter when a single tick takes longer than this. 0 disables the watchdog.",
+                "Watchdog Limit"
+                input {
+                    r#type: "number",
+                    min: 0,
+                    step: 0.5,
+                    value: "{watchdog_limit}",
+                    onkeydown: move |ev| ev.stop_propagation(),
+                    onchange: move |ev| {
+                        if let Ok(secs) = ev.value().parse::<f64>() {
+                            match watchdog::limit_from_secs(secs) {
+                                Ok(limit) => {
+                                    WATCHDOG.set_limit(limit);
+                                    watchdog_limit.set(secs);
+                                }
+                                Err(e) => logs.write().push_level(e, LogLevel::Warning),
+                            }
+                        }
+                    },
+                }
+                "s"
+            }
         }
     }
 }
@@ -0,0 +1,154 @@
+use std::{fmt::Display, str::FromStr};
+
+use dioxus::prelude::*;
+use livesplit_auto_splitting::LogLevel;
+
+use crate::{fake_game::FakeGame, ui::Toggle, LogEntries};
+
+use super::Widget;
+
+#[derive(Copy, Clone, PartialEq)]
+struct GameValues {
+    level: u32,
+    loading: bool,
+    paused: bool,
+    health: f32,
+    score: u32,
+}
+
+impl Default for GameValues {
+    fn default() -> Self {
+        Self {
+            level: 0,
+            loading: false,
+            paused: false,
+            health: 100.0,
+            score: 0,
+        }
+    }
+}
+
+#[component]
+pub fn FakeGameControl(logs: SyncSignal<LogEntries>) -> Element {
+    let mut game = use_signal(|| None::<FakeGame>);
+    let mut values = use_signal(GameValues::default);
+
+    let send = move |command: String| {
+        if let Some(game) = &mut *{ game }.write() {
+            if let Err(e) = game.send(&command) {
+                { logs }
+                    .write()
+                    .push_level(format!("{e:?}"), LogLevel::Error);
+            }
+        }
+    };
+
+    let launch = move |_| {
+        let launched = FakeGame::launch(move |line, level| {
+            { logs }
+                .write()
+                .push_level(format!("Fake game: {line}"), level);
+        });
+        match launched {
+            Ok(launched) => {
+                values.set(GameValues::default());
+                game.set(Some(launched));
+            }
+            Err(e) => {
+                { logs }
+                    .write()
+                    .push_level(format!("{e:?}"), LogLevel::Error);
+            }
+        }
+    };
+
+    let pid = game.read().as_ref().map(FakeGame::pid);
+    let current = values();
+
+    rsx! {
+        Widget { title: "Fake Game",
+            if let Some(pid) = pid {
+                div { class: "gray", "Running with PID {pid}" }
+                button { class: "stop", onclick: move |_| game.set(None), "Stop" }
+                div { class: "fake-game",
+                    NumberInput {
+                        label: "Level",
+                        value: current.level,
+                        onchange: move |level| {
+                            values.write().level = level;
+                            send(format!("level {level}"));
+                        },
+                    }
+                    NumberInput {
+                        label: "Health",
+                        value: current.health,
+                        onchange: move |health| {
+                            values.write().health = health;
+                            send(format!("health {health}"));
+                        },
+                    }
+                    NumberInput {
+                        label: "Score",
+                        value: current.score,
+                        onchange: move |score| {
+                            values.write().score = score;
+                            send(format!("score {score}"));
+                        },
+                    }
+                }
+                Toggle {
+                    checked: current.loading,
+                    onchange: move |ev: Event<FormData>| {
+                        values.write().loading = ev.checked();
+                        send(format!("loading {}", ev.checked() as u8));
+                    },
+                    "Loading"
+                }
+                Toggle {
+                    checked: current.paused,
+                    onchange: move |ev: Event<FormData>| {
+                        values.write().paused = ev.checked();
+                        send(format!("paused {}", ev.checked() as u8));
+                    },
+                    "Paused"
+                }
+                button {
+                    onclick: move |_| {
+                        values.set(GameValues::default());
+                        send("reset".into());
+                    },
+                    "Reset"
+                }
+            } else {
+                button {
+                    title: "Launch a fake game with a known memory layout to test auto splitters with",
+                    onclick: launch,
+                    "Launch"
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn NumberInput<T: Display + FromStr + Clone + PartialEq + 'static>(
+    label: &'static str,
+    value: T,
+    onchange: EventHandler<T>,
+) -> Element {
+    rsx! {
+        label {
+            "{label}"
+            input {
+                r#type: "number",
+                value: "{value}",
+                onkeydown: move |ev| ev.stop_propagation(),
+                onchange: move |ev| {
+                    if let Ok(value) = ev.value().parse() {
+                        onchange.call(value);
+                    }
+                },
+            }
+        }
+    }
+}
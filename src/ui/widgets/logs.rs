@@ -1,107 +1,591 @@
-use std::rc::Rc;
+use std::{
+    collections::{HashSet, VecDeque},
+    fmt, fs, iter,
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::LazyLock,
+};
 
-use dioxus::prelude::*;
+use dioxus::{desktop::window, html::geometry::PixelsVector2D, prelude::*};
 use dioxus_material_icons::MaterialIcon;
 use livesplit_auto_splitting::LogLevel;
 
-use crate::UTC_OFFSET;
+use crate::{
+    cli, level_name,
+    session_log::{self, SessionLog},
+    UTC_OFFSET,
+};
 
-use crate::ui::{FmtTime, Panel};
+use crate::ui::{FmtTime, Panel, SourceLocation};
 
 pub struct LogEntries {
-    entries: Vec<LogEntry>,
+    entries: VecDeque<LogEntry>,
+    /// The maximum number of entries that are kept. The oldest entries are
+    /// dropped first.
+    capacity: usize,
+    next_id: u64,
+    session_log: Option<SessionLog>,
 }
 
 impl LogEntries {
-    pub fn new() -> Self {
+    pub fn new(capacity: usize) -> Self {
         Self {
-            entries: Vec::new(),
+            entries: VecDeque::new(),
+            capacity: capacity.max(1),
+            next_id: 0,
+            session_log: None,
         }
     }
 
     pub fn push_level(&mut self, message: String, level: LogLevel) {
-        self.entries
-            .push(LogEntry::new(message, LogType::Runtime(level)));
+        self.add(LogEntry::runtime(message, level));
     }
 
-    pub fn push(&mut self, message: String) {
-        self.entries
-            .push(LogEntry::new(message, LogType::AutoSplitter));
+    /// Adds multiple entries at once, so the UI only needs to update once.
+    pub fn extend(&mut self, entries: impl IntoIterator<Item = LogEntry>) {
+        for entry in entries {
+            self.add(entry);
+        }
+    }
+
+    fn add(&mut self, entry: LogEntry) {
+        if let Some(session_log) = &mut self.session_log {
+            if let Err(e) = session_log.write_line(&entry.to_text()) {
+                // Logging the error to the session log would fail again.
+                self.session_log = None;
+                self.store(LogEntry::runtime(
+                    format!("{:?}", e.context("The session log got disabled.")),
+                    LogLevel::Error,
+                ));
+            }
+        }
+        self.store(entry);
+    }
+
+    fn store(&mut self, mut entry: LogEntry) {
+        // Auto splitters often log the same message on every tick, so
+        // consecutive repetitions are collapsed into a single entry.
+        if let Some(last) = self.entries.back_mut() {
+            if last.message == entry.message && last.ty == entry.ty {
+                last.repeats += 1;
+                if last.repeat_times.len() == MAX_REPEAT_TIMES {
+                    last.repeat_times.pop_front();
+                }
+                last.repeat_times.push_back(entry.time);
+                return;
+            }
+        }
+
+        entry.id = self.next_id;
+        self.next_id += 1;
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    pub fn has_session_log(&self) -> bool {
+        self.session_log.is_some()
+    }
+
+    /// Starts appending all the entries to the session log in the directory,
+    /// or the default directory if none is specified.
+    pub fn start_session_log(&mut self, dir: Option<&Path>) {
+        let session_log = match dir {
+            Some(dir) => SessionLog::open(dir),
+            None => session_log::default_dir().and_then(|dir| SessionLog::open(&dir)),
+        };
+        match session_log {
+            Ok(session_log) => {
+                let path = session_log.path();
+                self.session_log = Some(session_log);
+                self.push_level(
+                    format!("Writing the session log to {}.", path.display()),
+                    LogLevel::Info,
+                );
+            }
+            Err(e) => self.push_level(format!("{e:?}"), LogLevel::Error),
+        }
+    }
+
+    pub fn stop_session_log(&mut self) {
+        self.session_log = None;
+    }
+
+    /// Exports all the entries either as plain text or as JSON Lines.
+    pub fn export(&self, json_lines: bool) -> String {
+        let mut exported = String::new();
+        for entry in &self.entries {
+            if json_lines {
+                exported.push_str(&entry.to_json());
+            } else {
+                exported.push_str(&entry.to_text());
+            }
+            exported.push('\n');
+        }
+        exported
     }
 }
 
 pub struct LogEntry {
+    /// Identifies the entry across the entries getting dropped.
+    id: u64,
     time: time::OffsetDateTime,
     message: String,
     ty: LogType,
+    /// How often the message got repeated right after the first time.
+    repeats: u64,
+    /// The times of the most recent repetitions.
+    repeat_times: VecDeque<time::OffsetDateTime>,
 }
 
 impl LogEntry {
+    pub fn runtime(message: String, level: LogLevel) -> Self {
+        Self::new(message, LogType::Runtime(level))
+    }
+
+    pub fn auto_splitter(message: String) -> Self {
+        Self::new(message, LogType::AutoSplitter)
+    }
+
     fn new(message: String, ty: LogType) -> Self {
         Self {
+            id: 0,
             time: time::OffsetDateTime::now_utc().to_offset(*UTC_OFFSET.get().unwrap()),
             message,
             ty,
+            repeats: 0,
+            repeat_times: VecDeque::new(),
+        }
+    }
+
+    fn last_time(&self) -> time::OffsetDateTime {
+        self.repeat_times.back().copied().unwrap_or(self.time)
+    }
+
+    /// The height of the entry in the log view. Entries don't wrap, so this is
+    /// known without measuring it. Expanded entries show every repetition that
+    /// is still known.
+    fn height(&self, expanded: bool) -> f64 {
+        self.occurrences(expanded).count() as f64 * self.row_height()
+    }
+
+    fn row_height(&self) -> f64 {
+        self.message.lines().count().max(1) as f64 * LINE_HEIGHT + ROW_PADDING
+    }
+
+    /// The times of the occurrences that are shown as individual rows.
+    fn occurrences(&self, expanded: bool) -> impl Iterator<Item = time::OffsetDateTime> + '_ {
+        let repeats = if expanded { self.repeat_times.len() } else { 0 };
+        iter::once(self.time).chain(self.repeat_times.iter().copied().take(repeats))
+    }
+
+    fn to_text(&self) -> String {
+        let mut text = format!(
+            "{} [{}] {}",
+            FmtTimestamp(self.time),
+            self.ty.name(),
+            self.message,
+        );
+        if self.repeats != 0 {
+            text.push_str(&format!(
+                " (repeated {} more times until {})",
+                self.repeats,
+                FmtTimestamp(self.last_time()),
+            ));
+        }
+        text
+    }
+
+    fn to_json(&self) -> String {
+        let (ty, level) = match self.ty {
+            LogType::AutoSplitter => ("auto_splitter", None),
+            LogType::Runtime(level) => ("runtime", Some(level_name(level).to_lowercase())),
+        };
+        let mut json = serde_json::json!({
+            "time": FmtTimestamp(self.time).to_string(),
+            "type": ty,
+            "level": level,
+            "message": self.message,
+        });
+        if self.repeats != 0 {
+            json["repeats"] = self.repeats.into();
+            json["last_time"] = FmtTimestamp(self.last_time()).to_string().into();
         }
+        json.to_string()
+    }
+}
+
+/// Formats the time as an RFC 3339 timestamp with millisecond precision.
+struct FmtTimestamp(time::OffsetDateTime);
+
+impl fmt::Display for FmtTimestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let time = self.0;
+        let (offset_h, offset_m, _) = time.offset().as_hms();
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}{}{:02}:{:02}",
+            time.year(),
+            time.month() as u8,
+            time.day(),
+            time.hour(),
+            time.minute(),
+            time.second(),
+            time.millisecond(),
+            if time.offset().is_negative() {
+                '-'
+            } else {
+                '+'
+            },
+            offset_h.unsigned_abs(),
+            offset_m.unsigned_abs(),
+        )
     }
 }
 
+#[derive(PartialEq)]
 enum LogType {
     Runtime(LogLevel),
     AutoSplitter,
 }
 
+impl LogType {
+    fn name(&self) -> &'static str {
+        match self {
+            LogType::AutoSplitter => "Auto Splitter",
+            LogType::Runtime(level) => level_name(*level),
+        }
+    }
+}
+
+/// The height of a single line of a log entry in pixels.
+const LINE_HEIGHT: f64 = 20.0;
+
+/// The vertical padding and the border of a log entry in pixels.
+const ROW_PADDING: f64 = 6.0;
+
+/// How far beyond the visible part of the view the entries are rendered, so
+/// scrolling doesn't reveal any gaps.
+const OVERSCAN: f64 = 500.0;
+
+/// The number of repetitions of a log message whose times are kept.
+const MAX_REPEAT_TIMES: usize = 100;
+
+/// The levels in the order they are shown in the filter.
+const LEVELS: [LogLevel; 5] = [
+    LogLevel::Error,
+    LogLevel::Warning,
+    LogLevel::Info,
+    LogLevel::Debug,
+    LogLevel::Trace,
+];
+
+#[derive(Copy, Clone, PartialEq)]
+struct LogFilter {
+    auto_splitter: bool,
+    levels: [bool; LEVELS.len()],
+}
+
+impl LogFilter {
+    fn matches(&self, ty: &LogType) -> bool {
+        match ty {
+            LogType::AutoSplitter => self.auto_splitter,
+            LogType::Runtime(level) => LEVELS
+                .iter()
+                .position(|l| l == level)
+                .is_none_or(|index| self.levels[index]),
+        }
+    }
+}
+
+enum Search {
+    None,
+    Text(String),
+    Regex(regex::Regex),
+    Invalid,
+}
+
+impl PartialEq for Search {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Search::None, Search::None) | (Search::Invalid, Search::Invalid) => true,
+            (Search::Text(a), Search::Text(b)) => a == b,
+            (Search::Regex(a), Search::Regex(b)) => a.as_str() == b.as_str(),
+            _ => false,
+        }
+    }
+}
+
+impl Search {
+    fn matches(&self, message: &str) -> bool {
+        match self {
+            Search::None | Search::Invalid => true,
+            Search::Text(text) => message.to_lowercase().contains(text),
+            Search::Regex(regex) => regex.is_match(message),
+        }
+    }
+}
+
 #[component]
-pub fn Logs(logs: SyncSignal<LogEntries>, show_editor: Signal<bool>, height: f64) -> Element {
-    let mut element: Signal<Option<Rc<MountedData>>> = use_signal(|| None);
-    use_memo(move || {
-        if let Some(element) = &*element.read() {
-            drop(element.scroll_to(ScrollBehavior::Smooth));
+pub fn Logs(logs: SyncSignal<LogEntries>, onlocation: EventHandler<SourceLocation>) -> Element {
+    let mut filter = use_signal(|| LogFilter {
+        auto_splitter: true,
+        levels: [true; LEVELS.len()],
+    });
+    let mut search_text = use_signal(String::new);
+    let mut use_regex = use_signal(|| false);
+    let search = use_memo(move || {
+        let text = search_text.read();
+        if text.is_empty() {
+            Search::None
+        } else if use_regex() {
+            regex::RegexBuilder::new(&text)
+                .case_insensitive(true)
+                .build()
+                .map_or(Search::Invalid, Search::Regex)
+        } else {
+            Search::Text(text.to_lowercase())
         }
     });
 
+    // Automatically scrolling to the newest entry is paused while the user
+    // scrolled up to read the older ones.
+    let mut follow = use_signal(|| true);
+    let mut expanded = use_signal(HashSet::<u64>::new);
+    let mut scroll_top = use_signal(|| 0.0);
+    let mut view_height = use_signal(|| 0.0);
+    let mut view: Signal<Option<Rc<MountedData>>> = use_signal(|| None);
+
+    let scroll_to_bottom = move || {
+        if let Some(view) = &*view.peek() {
+            drop(view.scroll(PixelsVector2D::new(0.0, f64::MAX), ScrollBehavior::Instant));
+        }
+    };
+
+    use_effect(move || {
+        logs.read();
+        filter.read();
+        search.read();
+        if *follow.peek() {
+            scroll_to_bottom();
+        }
+    });
+
+    let export = move |_| async move {
+        let Some(file) = rfd::AsyncFileDialog::new()
+            .set_parent(&window().window)
+            .set_file_name("logs.txt")
+            .add_filter("Text", &["txt", "log"])
+            .add_filter("JSON Lines", &["jsonl"])
+            .save_file()
+            .await
+        else {
+            return;
+        };
+
+        let path = file.path();
+        let json_lines = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("jsonl"));
+        let exported = logs.read().export(json_lines);
+        if let Err(e) = fs::write(path, exported) {
+            logs.write().push_level(
+                format!(
+                    "{:?}",
+                    anyhow::Error::from(e).context("Failed exporting the logs.")
+                ),
+                LogLevel::Error,
+            );
+        }
+    };
+
     let current_logs = &*logs.read();
+    let current_filter = filter();
+    let expanded_entries = &*expanded.read();
+    let search = &*search.read();
+    let shown = current_logs
+        .entries
+        .iter()
+        .filter(|entry| current_filter.matches(&entry.ty) && search.matches(&entry.message))
+        .collect::<Vec<_>>();
+
+    // Only the entries within the visible part of the view are rendered. The
+    // rest is replaced by spacers of the same height.
+    let mut offsets = Vec::with_capacity(shown.len() + 1);
+    let mut offset = 0.0;
+    offsets.push(offset);
+    for entry in &shown {
+        offset += entry.height(expanded_entries.contains(&entry.id));
+        offsets.push(offset);
+    }
+    let total_height = offset;
+    let visible_start = scroll_top() - OVERSCAN;
+    let visible_end = scroll_top() + view_height() + OVERSCAN;
+    let start = offsets[1..].partition_point(|&end| end <= visible_start);
+    let end = offsets[..shown.len()]
+        .partition_point(|&start| start < visible_end)
+        .max(start);
 
     rsx! {
+        div { class: "log-filter",
+            button {
+                class: if current_filter.auto_splitter { "chip active" } else { "chip" },
+                onclick: move |_| filter.write().auto_splitter ^= true,
+                "Auto Splitter"
+            }
+            for (index , level) in LEVELS.into_iter().enumerate() {
+                button {
+                    class: if current_filter.levels[index] { "chip active" } else { "chip" },
+                    onclick: move |_| filter.write().levels[index] ^= true,
+                    "{level_name(level)}"
+                }
+            }
+            input {
+                r#type: "search",
+                class: if matches!(search, Search::Invalid) { "invalid" },
+                placeholder: "Search…",
+                flex_grow: 1,
+                value: "{search_text}",
+                // The timer shortcuts shouldn't trigger while typing.
+                onkeydown: move |ev| ev.stop_propagation(),
+                oninput: move |ev| search_text.set(ev.value()),
+            }
+            label { title: "Search with a regular expression.",
+                input {
+                    r#type: "checkbox",
+                    checked: use_regex(),
+                    onchange: move |ev| use_regex.set(ev.checked()),
+                }
+                ".*"
+            }
+            button {
+                class: if current_logs.has_session_log() { "chip active" } else { "chip" },
+                title: "Continuously append all the logs to a file on disk.",
+                onclick: move |_| {
+                    let logs = &mut *logs.write();
+                    if logs.has_session_log() {
+                        logs.stop_session_log();
+                    } else {
+                        logs.start_session_log(cli::args().session_log.as_ref().and_then(|dir| dir.as_deref()));
+                    }
+                },
+                "Session Log"
+            }
+            button {
+                title: "Export logs",
+                disabled: current_logs.entries.is_empty(),
+                onclick: export,
+                MaterialIcon { name: "save_alt", size: 20 }
+            }
+        }
         div {
-            flex_grow: if *show_editor.read() { "0" } else { "1" },
-            height: if *show_editor.read() { "{height}px" },
+            flex_grow: "1",
             position: "relative",
             display: "flex",
             flex_direction: "column",
             overflow: "hidden",
             Panel {
-                class: "logs",
                 min_height: "20px",
                 display: "flex",
                 flex_direction: "column",
-                gap: "5px",
                 flex_grow: "1",
-                for (i , entry) in current_logs.entries.iter().enumerate() {
-                    if i != 0 {
-                        hr { margin: "0" }
-                    }
-                    div {
-                        onmounted: move |cx| {
-                            if i + 1 == logs.read().entries.len() {
-                                element.set(Some(cx.data()));
+                div {
+                    class: "logs",
+                    flex_grow: "1",
+                    min_height: "0",
+                    onmounted: move |ev| async move {
+                        let data = ev.data();
+                        if let Ok(rect) = data.get_client_rect().await {
+                            view_height.set(rect.height());
+                        }
+                        view.set(Some(data));
+                        scroll_to_bottom();
+                    },
+                    onresize: move |ev| {
+                        if let Ok(size) = ev.get_content_box_size() {
+                            view_height.set(size.height);
+                        }
+                    },
+                    onscroll: move |ev| {
+                        let top = ev.scroll_top();
+                        let at_bottom = top + ev.client_height() as f64
+                            >= ev.scroll_height() as f64 - 5.0;
+                        if at_bottom {
+                            if !follow() {
+                                follow.set(true);
                             }
-                        },
-                        span {
-                            color: "rgba(255, 255, 255, 0.5)",
-                            padding_right: "10px",
-                            "{FmtTime(entry.time)}"
+                        } else if top < *scroll_top.peek() && follow() {
+                            follow.set(false);
                         }
-                        span {
-                            class: match entry.ty {
-                                LogType::AutoSplitter => "",
-                                LogType::Runtime(LogLevel::Error) => "error",
-                                LogType::Runtime(LogLevel::Warning) => "warn",
-                                _ => "info",
-                            },
-                            "{entry.message}"
+                        scroll_top.set(top);
+                    },
+                    div { height: "{offsets[start]}px" }
+                    for entry in &shown[start..end] {
+                        div { key: "{entry.id}",
+                            for (i , time) in entry.occurrences(expanded_entries.contains(&entry.id)).enumerate() {
+                                div { class: "log-row", height: "{entry.row_height()}px",
+                                    span {
+                                        color: "rgba(255, 255, 255, 0.5)",
+                                        padding_right: "10px",
+                                        "{FmtTime(time)}"
+                                    }
+                                    if i == 0 && entry.repeats != 0 {
+                                        button {
+                                            class: "repeat-count",
+                                            title: "Repeated until {FmtTime(entry.last_time())}. Click to show the repetitions.",
+                                            onclick: {
+                                                let id = entry.id;
+                                                move |_| {
+                                                    let expanded = &mut *expanded.write();
+                                                    if !expanded.remove(&id) {
+                                                        expanded.insert(id);
+                                                    }
+                                                }
+                                            },
+                                            "×{entry.repeats + 1}"
+                                        }
+                                    }
+                                    span {
+                                        class: match entry.ty {
+                                            LogType::AutoSplitter => "",
+                                            LogType::Runtime(LogLevel::Error) => "error",
+                                            LogType::Runtime(LogLevel::Warning) => "warn",
+                                            _ => "info",
+                                        },
+                                        for (text , location) in split_locations(&entry.message) {
+                                            if let Some(location) = location {
+                                                a {
+                                                    class: "source-link",
+                                                    title: "Show in the source viewer",
+                                                    onclick: move |_| onlocation.call(location.clone()),
+                                                    "{text}"
+                                                }
+                                            } else {
+                                                "{text}"
+                                            }
+                                        }
+                                    }
+                                }
+                            }
                         }
                     }
+                    div { height: "{total_height - offsets[end]}px" }
+                }
+            }
+            if !follow() {
+                button {
+                    title: "Scroll to the newest entry",
+                    position: "absolute",
+                    bottom: "20px",
+                    right: "60px",
+                    width: "32px",
+                    height: "32px",
+                    onclick: move |_| {
+                        follow.set(true);
+                        scroll_to_bottom();
+                    },
+                    MaterialIcon { name: "arrow_downward", size: 20 }
                 }
             }
             if !current_logs.entries.is_empty() {
@@ -122,3 +606,100 @@ pub fn Logs(logs: SyncSignal<LogEntries>, show_editor: Signal<bool>, height: f64
         }
     }
 }
+
+/// Matches source locations in backtraces (`at src/lib.rs:12:5`), panic
+/// messages (`panicked at src/lib.rs:12:5`) and compiler diagnostics
+/// (`--> src/lib.rs:12:5`). The path needs an extension, so times like
+/// `at 12:30` aren't mistaken for locations.
+static LOCATION_REGEX: LazyLock<regex::Regex> = LazyLock::new(|| {
+    regex::Regex::new(r"(?:\bat|-->) +((?:[A-Za-z]:)?[^\s:]*\.\w+):(\d+)(?::\d+)?").unwrap()
+});
+
+/// Splits the message into the parts that are plain text and the parts that
+/// refer to a location in a source file.
+fn split_locations(message: &str) -> Vec<(&str, Option<SourceLocation>)> {
+    let mut parts = Vec::new();
+    let mut last_end = 0;
+    for captures in LOCATION_REGEX.captures_iter(message) {
+        let (path, line) = (&captures[1], &captures[2]);
+        let path_start = captures.get(1).unwrap().start();
+        let end = captures.get(0).unwrap().end();
+        if last_end < path_start {
+            parts.push((&message[last_end..path_start], None));
+        }
+        parts.push((
+            &message[path_start..end],
+            Some(SourceLocation {
+                path: PathBuf::from(path),
+                line: line.parse().ok(),
+            }),
+        ));
+        last_end = end;
+    }
+    if last_end < message.len() {
+        parts.push((&message[last_end..], None));
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location(path: &str, line: usize) -> Option<SourceLocation> {
+        Some(SourceLocation {
+            path: PathBuf::from(path),
+            line: Some(line),
+        })
+    }
+
+    #[test]
+    fn finds_locations_in_backtraces() {
+        assert!(
+            split_locations("    3: update\n           at /src/lib.rs:12:5\n")
+                == [
+                    ("    3: update\n           at ", None),
+                    ("/src/lib.rs:12:5", location("/src/lib.rs", 12)),
+                    ("\n", None),
+                ]
+        );
+    }
+
+    #[test]
+    fn finds_locations_with_drive_letters() {
+        assert!(
+            split_locations(r"panicked at C:\project\src\lib.rs:7:13:")
+                == [
+                    ("panicked at ", None),
+                    (
+                        r"C:\project\src\lib.rs:7:13",
+                        location(r"C:\project\src\lib.rs", 7)
+                    ),
+                    (":", None),
+                ]
+        );
+    }
+
+    #[test]
+    fn finds_locations_in_compiler_errors() {
+        assert!(
+            split_locations("error: unused\n --> src/main.rs:3 and at lib.rs:4")
+                == [
+                    ("error: unused\n --> ", None),
+                    ("src/main.rs:3", location("src/main.rs", 3)),
+                    (" and at ", None),
+                    ("lib.rs:4", location("lib.rs", 4)),
+                ]
+        );
+    }
+
+    #[test]
+    fn ignores_text_without_locations() {
+        assert!(
+            split_locations("Attached at 12:30 to game.exe")
+                == [("Attached at 12:30 to game.exe", None)]
+        );
+        assert!(split_locations("that:12").len() == 1);
+        assert!(split_locations("").is_empty());
+    }
+}
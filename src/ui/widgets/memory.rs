@@ -0,0 +1,436 @@
+use dioxus::prelude::*;
+
+use crate::ui::Panel;
+
+const BYTES_PER_ROW: usize = 16;
+const ROWS: usize = 32;
+
+/// Only this much of the memory is copied out of the auto splitter on every
+/// tick.
+const WINDOW_LEN: usize = BYTES_PER_ROW * ROWS;
+
+/// The longest string that is shown when interpreting the selected bytes.
+const MAX_STRING_LEN: usize = 64;
+
+/// A window into the linear memory of the auto splitter. The runtime thread
+/// only copies the memory while the inspector is shown.
+#[derive(Default)]
+pub struct MemoryInspector {
+    active: bool,
+    address: usize,
+    selected: Option<usize>,
+    memory_len: usize,
+    /// The address the bytes were copied from, which lags behind `address`
+    /// until the next tick.
+    window_address: usize,
+    bytes: Vec<u8>,
+    /// The tick in which each of the bytes last changed.
+    changed_in: Vec<Option<u64>>,
+    search: Option<Vec<u8>>,
+    search_status: SearchStatus,
+}
+
+#[derive(Default, Copy, Clone, PartialEq)]
+enum SearchStatus {
+    #[default]
+    None,
+    Found,
+    NotFound,
+}
+
+impl MemoryInspector {
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Whether `update` would change anything, which avoids rerendering the
+    /// inspector on every tick.
+    pub fn is_outdated(&self, memory: &[u8]) -> bool {
+        self.search.is_some()
+            || self.memory_len != memory.len()
+            || self.window_address != self.address
+            || memory.get(self.window_address..self.window_address + self.bytes.len())
+                != Some(&self.bytes)
+    }
+
+    pub fn update(&mut self, memory: &[u8], tick: u64) {
+        self.memory_len = memory.len();
+        if let Some(needle) = self.search.take() {
+            self.run_search(memory, &needle);
+        }
+
+        let start = self.address.min(memory.len());
+        let new_bytes = &memory[start..(start + WINDOW_LEN).min(memory.len())];
+        if self.window_address == start && self.bytes.len() == new_bytes.len() {
+            for ((byte, &new_byte), changed_in) in self
+                .bytes
+                .iter_mut()
+                .zip(new_bytes)
+                .zip(&mut self.changed_in)
+            {
+                if *byte != new_byte {
+                    *byte = new_byte;
+                    *changed_in = Some(tick);
+                }
+            }
+        } else {
+            self.window_address = start;
+            self.bytes = new_bytes.to_vec();
+            self.changed_in = vec![None; new_bytes.len()];
+        }
+    }
+
+    /// Searches for the next occurrence after the selected byte, wrapping
+    /// around at the end of the memory.
+    fn run_search(&mut self, memory: &[u8], needle: &[u8]) {
+        let from = self.selected.map_or(self.address, |selected| selected + 1);
+        let found = find(memory.get(from..).unwrap_or_default(), needle)
+            .map(|index| from + index)
+            .or_else(|| find(memory, needle));
+        match found {
+            Some(index) => {
+                self.selected = Some(index);
+                self.address = row_start(index);
+                self.search_status = SearchStatus::Found;
+            }
+            None => self.search_status = SearchStatus::NotFound,
+        }
+    }
+
+    fn go_to(&mut self, address: usize) {
+        self.address = row_start(address.min(self.memory_len.saturating_sub(1)));
+    }
+
+    /// The byte at the address along with the tick it last changed in.
+    fn byte(&self, address: usize) -> Option<(u8, Option<u64>)> {
+        let offset = address.checked_sub(self.window_address)?;
+        Some((*self.bytes.get(offset)?, self.changed_in[offset]))
+    }
+
+    /// The bytes starting at the address, as far as they are in the window.
+    fn bytes_at(&self, address: usize) -> &[u8] {
+        address
+            .checked_sub(self.window_address)
+            .and_then(|offset| self.bytes.get(offset..))
+            .unwrap_or_default()
+    }
+}
+
+fn row_start(address: usize) -> usize {
+    address - address % BYTES_PER_ROW
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    if needle.is_empty() {
+        return None;
+    }
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum SearchMode {
+    Hex,
+    Text,
+    U32,
+    F32,
+}
+
+impl SearchMode {
+    const ALL: [SearchMode; 4] = [
+        SearchMode::Hex,
+        SearchMode::Text,
+        SearchMode::U32,
+        SearchMode::F32,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            SearchMode::Hex => "Hex",
+            SearchMode::Text => "Text",
+            SearchMode::U32 => "u32",
+            SearchMode::F32 => "f32",
+        }
+    }
+
+    fn parse(self, text: &str) -> Option<Vec<u8>> {
+        match self {
+            SearchMode::Hex => {
+                let digits = text
+                    .chars()
+                    .filter(|c| !c.is_whitespace())
+                    .collect::<String>();
+                if digits.len() % 2 != 0 {
+                    return None;
+                }
+                (0..digits.len())
+                    .step_by(2)
+                    .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).ok())
+                    .collect()
+            }
+            SearchMode::Text => Some(text.as_bytes().to_vec()),
+            SearchMode::U32 => Some(parse_address(text)?.to_le_bytes().to_vec()),
+            SearchMode::F32 => Some(text.trim().parse::<f32>().ok()?.to_le_bytes().to_vec()),
+        }
+    }
+}
+
+/// Parses decimal numbers and hexadecimal numbers prefixed with `0x`.
+fn parse_address(text: &str) -> Option<u32> {
+    let text = text.trim();
+    match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
+
+#[component]
+pub fn MemoryView(mut inspector: SyncSignal<MemoryInspector>) -> Element {
+    use_hook(move || inspector.write().active = true);
+    use_drop(move || inspector.write().active = false);
+
+    let mut search_text = use_signal(String::new);
+    let mut search_mode = use_signal(|| SearchMode::Hex);
+    let needle = use_memo(move || search_mode().parse(&search_text.read()));
+
+    let state = &*inspector.read();
+    let selected = state.selected;
+
+    rsx! {
+        div { class: "memory-toolbar",
+            input {
+                class: "memory-address",
+                title: "The address to go to, either decimal or hexadecimal with a 0x prefix",
+                value: "0x{state.address:08X}",
+                onkeydown: move |ev| ev.stop_propagation(),
+                onchange: move |ev| {
+                    if let Some(address) = parse_address(&ev.value()) {
+                        inspector.write().go_to(address as usize);
+                    }
+                },
+            }
+            button {
+                title: "Previous page",
+                onclick: move |_| {
+                    let inspector = &mut *inspector.write();
+                    let address = inspector.address.saturating_sub(WINDOW_LEN);
+                    inspector.go_to(address);
+                },
+                "◀"
+            }
+            button {
+                title: "Next page",
+                onclick: move |_| {
+                    let inspector = &mut *inspector.write();
+                    let address = inspector.address + WINDOW_LEN;
+                    inspector.go_to(address);
+                },
+                "▶"
+            }
+            span { class: "gray", "{state.memory_len} bytes" }
+            select {
+                onchange: move |ev| {
+                    if let Some(mode) = SearchMode::ALL.into_iter().find(|m| m.name() == ev.value()) {
+                        search_mode.set(mode);
+                    }
+                },
+                for mode in SearchMode::ALL {
+                    option { selected: mode == search_mode(), "{mode.name()}" }
+                }
+            }
+            input {
+                r#type: "search",
+                class: if !search_text.read().is_empty() && needle.read().is_none() { "invalid" },
+                placeholder: "Search…",
+                flex_grow: 1,
+                value: "{search_text}",
+                onkeydown: move |ev| {
+                    ev.stop_propagation();
+                    if ev.key() == Key::Enter {
+                        if let Some(needle) = needle() {
+                            inspector.write().search = Some(needle);
+                        }
+                    }
+                },
+                oninput: move |ev| search_text.set(ev.value()),
+            }
+            button {
+                disabled: needle.read().is_none(),
+                onclick: move |_| {
+                    if let Some(needle) = needle() {
+                        inspector.write().search = Some(needle);
+                    }
+                },
+                "Find Next"
+            }
+            if state.search_status == SearchStatus::NotFound {
+                span { class: "warn", "Not found" }
+            }
+        }
+        div { display: "flex", gap: "10px", flex_grow: "1", min_height: "0",
+            // Scrolling moves through the memory, as only the window of it
+            // that is shown is copied.
+            div {
+                display: "flex",
+                flex_grow: "1",
+                onwheel: move |ev: WheelEvent| {
+                    let rows = (ev.delta().strip_units().y / 20.0).round() as isize;
+                    let inspector = &mut *inspector.write();
+                    let address = inspector
+                        .address
+                        .saturating_add_signed(rows * BYTES_PER_ROW as isize);
+                    inspector.go_to(address);
+                },
+                Panel {
+                    class: "memory",
+                    flex_grow: "1",
+                    if state.bytes.is_empty() {
+                        div { class: "gray", "There is no auto splitter memory to show." }
+                    }
+                    for row in (0..state.bytes.len())
+                        .step_by(BYTES_PER_ROW)
+                        .map(|offset| state.window_address + offset)
+                    {
+                        div { class: "memory-row",
+                            span { class: "gray", "{row:08X}" }
+                            span { class: "memory-hex",
+                                for address in row..row + BYTES_PER_ROW {
+                                    if let Some((byte, changed_in)) = state.byte(address) {
+                                        ByteCell {
+                                            key: "{address}-{changed_in:?}",
+                                            address,
+                                            text: format!("{byte:02X}"),
+                                            changed: changed_in.is_some(),
+                                            selected: selected == Some(address),
+                                            onselect: move |address| inspector.write().selected = Some(address),
+                                        }
+                                    }
+                                }
+                            }
+                            span {
+                                for address in row..row + BYTES_PER_ROW {
+                                    if let Some((byte, changed_in)) = state.byte(address) {
+                                        ByteCell {
+                                            key: "{address}-{changed_in:?}",
+                                            address,
+                                            text: if byte.is_ascii_graphic() || byte == b' ' { (byte as char).to_string() } else { ".".into() },
+                                            changed: changed_in.is_some(),
+                                            selected: selected == Some(address),
+                                            onselect: move |address| inspector.write().selected = Some(address),
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            Panel { class: "memory-values",
+                if let Some(selected) = selected {
+                    Interpretation {
+                        address: selected,
+                        bytes: state.bytes_at(selected).to_vec(),
+                        onfollow: move |address: u32| {
+                            let inspector = &mut *inspector.write();
+                            inspector.selected = Some(address as usize);
+                            inspector.go_to(address as usize);
+                        },
+                    }
+                } else {
+                    div { class: "gray", "Select a byte to interpret the memory at it." }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn ByteCell(
+    address: usize,
+    text: String,
+    changed: bool,
+    selected: bool,
+    onselect: EventHandler<usize>,
+) -> Element {
+    rsx! {
+        span {
+            class: "memory-byte",
+            class: if changed { "changed" },
+            class: if selected { "selected" },
+            onclick: move |_| onselect.call(address),
+            "{text}"
+        }
+    }
+}
+
+/// Shows the bytes at the selected address as the different types.
+#[component]
+fn Interpretation(address: usize, bytes: Vec<u8>, onfollow: EventHandler<u32>) -> Element {
+    fn read<const N: usize>(bytes: &[u8]) -> Option<[u8; N]> {
+        bytes.get(..N)?.try_into().ok()
+    }
+
+    let string_bytes = &bytes[..bytes.len().min(MAX_STRING_LEN)];
+    let string_bytes = string_bytes.split(|&b| b == 0).next().unwrap_or_default();
+    let string = String::from_utf8_lossy(string_bytes);
+    let pointer = read(&bytes).map(u32::from_le_bytes);
+
+    let values = [
+        ("u8", read(&bytes).map(|b| u8::from_le_bytes(b).to_string())),
+        ("i8", read(&bytes).map(|b| i8::from_le_bytes(b).to_string())),
+        (
+            "u16",
+            read(&bytes).map(|b| u16::from_le_bytes(b).to_string()),
+        ),
+        (
+            "i16",
+            read(&bytes).map(|b| i16::from_le_bytes(b).to_string()),
+        ),
+        ("u32", pointer.map(|v| v.to_string())),
+        (
+            "i32",
+            read(&bytes).map(|b| i32::from_le_bytes(b).to_string()),
+        ),
+        (
+            "u64",
+            read(&bytes).map(|b| u64::from_le_bytes(b).to_string()),
+        ),
+        (
+            "i64",
+            read(&bytes).map(|b| i64::from_le_bytes(b).to_string()),
+        ),
+        (
+            "f32",
+            read(&bytes).map(|b| f32::from_le_bytes(b).to_string()),
+        ),
+        (
+            "f64",
+            read(&bytes).map(|b| f64::from_le_bytes(b).to_string()),
+        ),
+        ("UTF-8", Some(format!("{string:?}"))),
+    ];
+
+    rsx! {
+        div { class: "gray", "0x{address:08X}" }
+        div { class: "table",
+            for (name , value) in values {
+                div { "{name}" }
+                div { class: "memory-value",
+                    if let Some(value) = value {
+                        "{value}"
+                    } else {
+                        span { class: "gray", "—" }
+                    }
+                }
+            }
+        }
+        if let Some(pointer) = pointer {
+            button {
+                title: "Interpret the u32 as a pointer and go to it",
+                onclick: move |_| onfollow.call(pointer),
+                "Follow Pointer"
+            }
+        }
+    }
+}
@@ -1,19 +1,27 @@
 use dioxus::prelude::*;
 
 mod auto_splitter_control;
+mod fake_game_control;
 mod logs;
+mod memory;
+mod module_info;
 mod processes;
 mod settings_gui;
 mod settings_map;
 mod statistics;
+mod timeline;
 mod timer_info;
 
 pub use auto_splitter_control::*;
+pub use fake_game_control::*;
 pub use logs::*;
+pub use memory::*;
+pub use module_info::*;
 pub use processes::*;
 pub use settings_gui::*;
 pub use settings_map::*;
 pub use statistics::*;
+pub use timeline::*;
 pub use timer_info::*;
 
 #[component]
@@ -0,0 +1,89 @@
+use dioxus::prelude::*;
+
+use crate::{
+    module_info::{ImportStatus, ModuleInfo},
+    ui::FmtBytes,
+};
+
+use super::Widget;
+
+#[component]
+pub fn ModuleInfoView(module_info: SyncSignal<Option<ModuleInfo>>) -> Element {
+    rsx! {
+        Widget { title: "Module",
+            if let Some(info) = &*module_info.read() {
+                ModuleDetails { info: info.clone() }
+            }
+        }
+    }
+}
+
+#[component]
+fn ModuleDetails(info: ModuleInfo) -> Element {
+    let import_count = info.imports.values().map(Vec::len).sum::<usize>();
+    let unsupported_count = info.unsupported_imports().count();
+
+    rsx! {
+        div { class: "table",
+            div { "Size" }
+            div { "{FmtBytes(info.total_size as _)}" }
+            div { "Code" }
+            div { "{FmtBytes(info.code_size as _)}" }
+            div { "Data" }
+            div { "{FmtBytes(info.data_size as _)}" }
+            div { "Debug Info" }
+            div {
+                if info.has_dwarf {
+                    "DWARF"
+                } else {
+                    "None"
+                }
+            }
+        }
+        details { class: "module-info", open: unsupported_count > 0,
+            summary {
+                "Imports ({import_count})"
+                if unsupported_count > 0 {
+                    span { class: "unsupported", " – {unsupported_count} unsupported" }
+                }
+            }
+            for (namespace , imports) in &info.imports {
+                div { class: "module-namespace", "{namespace}" }
+                for import in imports {
+                    div {
+                        class: match import.status {
+                            ImportStatus::Expected => "module-item",
+                            ImportStatus::Suspicious => "module-item suspicious",
+                            ImportStatus::Missing => "module-item unsupported",
+                        },
+                        title: match import.status {
+                            ImportStatus::Expected => "",
+                            ImportStatus::Suspicious => "The runtime most likely doesn't provide this import, as it's not one of the functions it is known to provide.",
+                            ImportStatus::Missing => "The runtime doesn't provide this import.",
+                        },
+                        span { "{import.name}" }
+                        span { class: "module-kind", "{import.kind}" }
+                    }
+                }
+            }
+        }
+        details { class: "module-info",
+            summary { "Exports ({info.exports.len()})" }
+            for export in &info.exports {
+                div { class: "module-item",
+                    span { "{export.name}" }
+                    span { class: "module-kind", "{export.kind}" }
+                }
+            }
+        }
+        details { class: "module-info",
+            summary { "Custom Sections ({info.custom_sections.len()})" }
+            for section in &info.custom_sections {
+                div { class: "module-item",
+                    span { "{section.name}" }
+                    span { class: "module-kind", "{FmtBytes(section.size as _)}" }
+                }
+            }
+        }
+    }
+}
@@ -1,21 +1,96 @@
-use dioxus::prelude::*;
+use std::path::PathBuf;
+
+use dioxus::{desktop::window, prelude::*};
+use dioxus_material_icons::MaterialIcon;
 use livesplit_auto_splitting::{
     settings::{self, Value},
-    AutoSplitter,
+    AutoSplitter, LogLevel,
 };
 
 use crate::{
-    ui::{Panel, Widget},
+    settings_file,
+    ui::{LogEntries, Panel, Widget},
     IdeTimer,
 };
 
 #[component]
 pub fn SettingsMap(
     settings_map: SyncSignal<settings::Map>,
+    settings_restored: SyncSignal<bool>,
+    wasm_path: SyncSignal<Option<PathBuf>>,
     auto_splitter: SyncSignal<Option<AutoSplitter<IdeTimer>>>,
+    logs: SyncSignal<LogEntries>,
 ) -> Element {
+    let import = move |_| async move {
+        let Some(file) = rfd::AsyncFileDialog::new()
+            .set_parent(&window().window)
+            .add_filter("Auto Splitter Settings", &["lss", "lsl", "xml", "json"])
+            .add_filter("All Files", &["*"])
+            .pick_file()
+            .await
+        else {
+            return;
+        };
+
+        match settings_file::load(file.path()) {
+            Ok(map) => {
+                if let Some(auto_splitter) = auto_splitter.read().as_ref() {
+                    auto_splitter.set_settings_map(map);
+                    settings_map.set(auto_splitter.settings_map());
+                }
+                logs.write()
+                    .push_level("Settings imported.".into(), LogLevel::Info);
+            }
+            Err(e) => {
+                logs.write().push_level(
+                    format!("{:?}", e.context("Failed importing the settings.")),
+                    LogLevel::Error,
+                );
+            }
+        }
+    };
+
+    let export = move |_| async move {
+        let Some(file) = rfd::AsyncFileDialog::new()
+            .set_parent(&window().window)
+            .add_filter("LiveSplit Auto Splitter Settings", &["xml"])
+            .add_filter("JSON", &["json"])
+            .set_file_name("settings.xml")
+            .save_file()
+            .await
+        else {
+            return;
+        };
+
+        if let Err(e) = settings_file::save(file.path(), &settings_map.read()) {
+            logs.write().push_level(
+                format!("{:?}", e.context("Failed exporting the settings.")),
+                LogLevel::Error,
+            );
+        }
+    };
+
+    let mut reset = move || {
+        if let Some(auto_splitter) = auto_splitter.read().as_ref() {
+            auto_splitter.set_settings_map(settings::Map::new());
+            settings_map.set(auto_splitter.settings_map());
+        }
+        settings_restored.set(false);
+    };
+
     rsx! {
         Widget { title: "Settings Map",
+            if settings_restored() {
+                div {
+                    class: "info",
+                    display: "flex",
+                    align_items: "center",
+                    gap: "8px",
+                    title: "The settings were saved in a previous session.",
+                    MaterialIcon { name: "history", size: 18 }
+                    "Restored saved settings"
+                }
+            }
             if !settings_map.read().is_empty() {
                 div { class: "table",
                     for (key , value) in settings_map.read().iter() {
@@ -23,14 +98,30 @@ pub fn SettingsMap(
                         div { class: "setting-value", {show_value(value)} }
                     }
                 }
+                div { display: "flex", gap: "5px",
+                    button { flex_grow: 1, onclick: move |_| reset(), "Clear" }
+                    button { flex_grow: 1, onclick: export, "Export" }
+                }
+            }
+            if auto_splitter.read().is_some() {
+                button {
+                    title: "Imports the settings from a LiveSplit splits or layout file.",
+                    onclick: import,
+                    "Import"
+                }
+            }
+            if settings_restored() {
                 button {
+                    title: "Resets the settings and stops restoring them when the auto splitter gets opened.",
                     onclick: move |_| {
-                        if let Some(auto_splitter) = auto_splitter.read().as_ref() {
-                            auto_splitter.set_settings_map(settings::Map::new());
-                            settings_map.set(auto_splitter.settings_map());
+                        if let Some(path) = &*wasm_path.read() {
+                            if let Err(e) = settings_file::forget_saved(path) {
+                                logs.write().push_level(format!("{e:?}"), LogLevel::Error);
+                            }
                         }
+                        reset();
                     },
-                    "Clear"
+                    "Forget Saved Settings"
                 }
             }
         }
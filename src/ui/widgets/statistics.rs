@@ -1,40 +1,302 @@
-use std::time::Duration;
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::BufWriter,
+    thread,
+    time::{Duration, Instant},
+};
 
-use dioxus::prelude::*;
+use anyhow::Context;
+use dioxus::{desktop::window, prelude::*};
+use livesplit_auto_splitting::LogLevel;
 
-use crate::ui::FmtDuration;
+use crate::{
+    trace::TickTrace,
+    ui::{value_range, FmtBytes, FmtDuration, Sparkline, SparklineRange},
+};
 
-use super::Widget;
+use super::{LogEntries, Widget};
+
+/// The number of most recent ticks that the percentiles and the histogram are
+/// calculated from.
+const WINDOW_LEN: usize = 1000;
+
+const HISTOGRAM_BUCKETS: usize = 20;
+
+/// How often the values get sampled for the charts. Leaks only show up over
+/// minutes, so the samples are much further apart than the ticks.
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The number of samples the charts show, which is 10 minutes worth of them.
+const HISTORY_LEN: usize = 600;
+
+const CHART_HEIGHT: f64 = 30.0;
+
+#[derive(Copy, Clone)]
+struct Sample {
+    avg_tick_secs: f64,
+    /// The slowest tick since the previous sample, so spikes don't get lost
+    /// between the samples.
+    tick_time: Duration,
+    memory_usage: usize,
+    handles: u64,
+}
 
 #[derive(Default)]
 pub struct StatisticsData {
+    /// The number of ticks executed so far.
+    pub ticks: u64,
     pub tick_rate: Duration,
     pub avg_tick_secs: f64,
     pub slowest_tick: Duration,
+    tick_times: VecDeque<Duration>,
     pub handles: u64,
     pub memory_usage: usize,
+    history: VecDeque<Sample>,
+    slowest_since_sample: Duration,
+    last_sample: Option<Instant>,
+}
+
+impl StatisticsData {
+    pub fn record_tick_time(&mut self, time_of_tick: Duration) {
+        if time_of_tick > self.slowest_tick {
+            self.slowest_tick = time_of_tick;
+        }
+        self.avg_tick_secs = self.avg_tick_secs * 0.999 + time_of_tick.as_secs_f64() * 0.001;
+        if self.tick_times.len() == WINDOW_LEN {
+            self.tick_times.pop_front();
+        }
+        self.tick_times.push_back(time_of_tick);
+        self.slowest_since_sample = self.slowest_since_sample.max(time_of_tick);
+    }
+
+    /// Adds the current values to the history of the charts, unless the last
+    /// sample was taken too recently.
+    pub fn sample(&mut self) {
+        let now = Instant::now();
+        if self
+            .last_sample
+            .is_some_and(|last| now.duration_since(last) < SAMPLE_INTERVAL)
+        {
+            return;
+        }
+        self.last_sample = Some(now);
+        if self.history.len() == HISTORY_LEN {
+            self.history.pop_front();
+        }
+        self.history.push_back(Sample {
+            avg_tick_secs: self.avg_tick_secs,
+            tick_time: std::mem::take(&mut self.slowest_since_sample),
+            memory_usage: self.memory_usage,
+            handles: self.handles,
+        });
+    }
+
+    /// Resets the measurements of the tick times. The number of ticks is kept,
+    /// as the timeline and the variable history refer to the ticks by it.
+    pub fn reset(&mut self) {
+        self.avg_tick_secs = 0.0;
+        self.slowest_tick = Duration::ZERO;
+        self.tick_times.clear();
+        self.history.clear();
+        self.slowest_since_sample = Duration::ZERO;
+        self.last_sample = None;
+    }
 }
 
 #[component]
-pub fn Statistics(statistics: SyncSignal<StatisticsData>) -> Element {
-    let statistics = &*statistics.read();
+pub fn Statistics(
+    mut statistics: SyncSignal<StatisticsData>,
+    trace: SyncSignal<TickTrace>,
+    mut logs: SyncSignal<LogEntries>,
+) -> Element {
+    let mut show_histogram = use_signal(|| false);
+
+    let export_trace = move |_| async move {
+        let Some(file) = rfd::AsyncFileDialog::new()
+            .set_parent(&window().window)
+            .set_file_name("trace.json")
+            .add_filter("Chrome Trace", &["json"])
+            .save_file()
+            .await
+        else {
+            return;
+        };
+
+        // Writing the file takes a while for long sessions, so it happens on
+        // a separate thread with a snapshot of the trace.
+        let trace = trace.read().clone();
+        let path = file.path().to_path_buf();
+        thread::spawn(move || {
+            let result = File::create(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|file| Ok(trace.export(BufWriter::new(file))?))
+                .context("Failed exporting the trace.");
+            match result {
+                Ok(()) => logs.write().push_level(
+                    format!("Trace exported to {}.", path.display()),
+                    LogLevel::Info,
+                ),
+                Err(e) => logs.write().push_level(format!("{e:?}"), LogLevel::Error),
+            }
+        });
+    };
+
+    let stats = &*statistics.read();
+    let mut sorted = stats.tick_times.iter().copied().collect::<Vec<_>>();
+    sorted.sort_unstable();
+    let percentile = |p: usize| {
+        // Nearest-rank method
+        let rank = (sorted.len() * p).div_ceil(100).max(1);
+        FmtDuration(sorted[rank - 1])
+    };
+
     rsx! {
         Widget { title: "Statistics",
             div { class: "table",
+                div { "Ticks" }
+                div { "{stats.ticks}" }
                 div { "Tick Rate" }
-                div { "{FmtDuration(statistics.tick_rate)}" }
+                div { "{FmtDuration(stats.tick_rate)}" }
                 div { "Avg. Tick Rate" }
-                div { "{FmtDuration(Duration::from_secs_f64(statistics.avg_tick_secs))}" }
+                div { "{FmtDuration(Duration::from_secs_f64(stats.avg_tick_secs))}" }
                 div { "Slowest Tick" }
-                div { "{FmtDuration(statistics.slowest_tick)}" }
+                div { "{FmtDuration(stats.slowest_tick)}" }
+                if !sorted.is_empty() {
+                    div { "p50" }
+                    div { "{percentile(50)}" }
+                    div { "p95" }
+                    div { "{percentile(95)}" }
+                    div { "p99" }
+                    div { "{percentile(99)}" }
+                }
                 div { "Handles" }
-                div { "{statistics.handles}" }
+                div { "{stats.handles}" }
                 div { "Memory" }
+                div { "{FmtBytes(stats.memory_usage as _)}" }
+            }
+            if stats.history.len() > 1 {
+                div { class: "charts",
+                    Chart {
+                        label: "Avg. Tick Rate",
+                        values: stats.history.iter().map(|s| s.avg_tick_secs).collect::<Vec<_>>(),
+                        unit: Unit::Seconds,
+                    }
+                    Chart {
+                        label: "Slowest Tick",
+                        values: stats.history.iter().map(|s| s.tick_time.as_secs_f64()).collect::<Vec<_>>(),
+                        unit: Unit::Seconds,
+                    }
+                    Chart {
+                        label: "Memory",
+                        values: stats.history.iter().map(|s| s.memory_usage as f64).collect::<Vec<_>>(),
+                        unit: Unit::Bytes,
+                    }
+                    Chart {
+                        label: "Handles",
+                        values: stats.history.iter().map(|s| s.handles as f64).collect::<Vec<_>>(),
+                        unit: Unit::Count,
+                    }
+                }
+            }
+            if *show_histogram.read() && !sorted.is_empty() {
+                Histogram { sorted }
+            }
+            div { display: "flex", gap: "5px",
+                button {
+                    flex_grow: 1,
+                    onclick: move |_| *show_histogram.write() ^= true,
+                    if *show_histogram.read() {
+                        "Hide Histogram"
+                    } else {
+                        "Show Histogram"
+                    }
+                }
+                button {
+                    flex_grow: 1,
+                    title: "Reset the tick time measurements.",
+                    onclick: move |_| statistics.write().reset(),
+                    "Reset"
+                }
+                button {
+                    flex_grow: 1,
+                    title: "Export the timings of the recorded ticks in the Chrome Trace Event format, which Perfetto and chrome://tracing can open.",
+                    disabled: trace.peek().is_empty(),
+                    onclick: export_trace,
+                    "Export Trace"
+                }
+            }
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum Unit {
+    Seconds,
+    Bytes,
+    Count,
+}
+
+impl Unit {
+    fn format(self, value: f64) -> String {
+        match self {
+            Unit::Seconds => FmtDuration(Duration::from_secs_f64(value)).to_string(),
+            Unit::Bytes => FmtBytes(value as _).to_string(),
+            Unit::Count => value.to_string(),
+        }
+    }
+}
+
+/// Plots the sampled values over time. The range is fitted to the values, so
+/// even a slow increase is visible as a trend.
+#[component]
+fn Chart(label: &'static str, values: Vec<f64>, unit: Unit) -> Element {
+    let (min, max) = value_range(values.iter().copied());
+
+    rsx! {
+        div { class: "chart",
+            div { class: "chart-header",
+                span { "{label}" }
+                span { "{unit.format(values[values.len() - 1])}" }
+            }
+            Sparkline {
+                values: values.iter().enumerate().map(|(i, &v)| (i as f64, v)).collect::<Vec<_>>(),
+                min,
+                max,
+                height: CHART_HEIGHT,
+                steps: false,
+            }
+            SparklineRange { start: "Min {unit.format(min)}", end: "Max {unit.format(max)}" }
+        }
+    }
+}
+
+/// Shows the distribution of the tick times within the window.
+#[component]
+fn Histogram(sorted: Vec<Duration>) -> Element {
+    let max = sorted[sorted.len() - 1].max(Duration::from_nanos(HISTOGRAM_BUCKETS as u64));
+    let bucket_size = max / HISTOGRAM_BUCKETS as u32;
+
+    let mut buckets = [0usize; HISTOGRAM_BUCKETS];
+    for time in &sorted {
+        let index = (time.as_nanos() / bucket_size.as_nanos()) as usize;
+        buckets[index.min(HISTOGRAM_BUCKETS - 1)] += 1;
+    }
+    let highest = buckets.iter().copied().max().unwrap_or(1);
+
+    rsx! {
+        div { class: "histogram",
+            for (i , count) in buckets.into_iter().enumerate() {
                 div {
-                    "{byte_unit::Byte::from_u64(statistics.memory_usage as _)
-                        .get_appropriate_unit(byte_unit::UnitType::Binary)}"
+                    class: "histogram-bar",
+                    height: "{count as f64 / highest as f64 * 100.0}%",
+                    title: "{FmtDuration(bucket_size * i as u32)} – {FmtDuration(bucket_size * (i as u32 + 1))}: {count} ticks",
                 }
             }
         }
+        div { class: "sparkline-range",
+            span { "0" }
+            span { "{FmtDuration(max)}" }
+        }
     }
 }
@@ -0,0 +1,237 @@
+use std::{collections::VecDeque, fmt};
+
+use dioxus::prelude::*;
+use dioxus_material_icons::MaterialIcon;
+
+use crate::{
+    ui::{FmtDuration, FmtTime, Panel},
+    UTC_OFFSET,
+};
+
+/// The auto splitter may call some of the functions on every tick, so only the
+/// most recent events are kept.
+const CAPACITY: usize = 100_000;
+
+/// Only the most recent matching events are rendered, as rendering all of them
+/// would slow down the UI considerably.
+const MAX_SHOWN: usize = 500;
+
+/// The timeline is rendered after every tick that causes an event, so only
+/// this many of the most recent events are searched for the matching ones.
+const MAX_SCANNED: usize = 10_000;
+
+/// Every call of the `Timer` functions, in the order they happened.
+pub struct TimerEvents {
+    entries: VecDeque<TimerEventEntry>,
+}
+
+impl TimerEvents {
+    pub fn new() -> Self {
+        Self {
+            entries: VecDeque::new(),
+        }
+    }
+
+    pub fn extend(&mut self, entries: impl IntoIterator<Item = TimerEventEntry>) {
+        for entry in entries {
+            if self.entries.len() == CAPACITY {
+                self.entries.pop_front();
+            }
+            self.entries.push_back(entry);
+        }
+    }
+}
+
+pub struct TimerEventEntry {
+    tick: u64,
+    time: time::OffsetDateTime,
+    category: Category,
+    text: String,
+    /// The lowercase text that the search is matched against.
+    search_text: String,
+    source: TimerEventSource,
+    /// Whether the call had any effect on the timer. A split while the timer
+    /// isn't running is ignored, for example.
+    applied: bool,
+}
+
+impl TimerEventEntry {
+    pub fn new(tick: u64, kind: &TimerEventKind, source: TimerEventSource, applied: bool) -> Self {
+        let text = kind.to_string();
+        Self {
+            tick,
+            time: time::OffsetDateTime::now_utc().to_offset(*UTC_OFFSET.get().unwrap()),
+            category: kind.category(),
+            search_text: text.to_lowercase(),
+            text,
+            source,
+            applied,
+        }
+    }
+}
+
+/// Who called the function. The timer can also be controlled manually, which
+/// shouldn't be mistaken for something the auto splitter did.
+#[derive(Copy, Clone, PartialEq)]
+pub enum TimerEventSource {
+    AutoSplitter,
+    Manual,
+}
+
+impl fmt::Display for TimerEventSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimerEventSource::AutoSplitter => write!(f, "Auto Splitter"),
+            TimerEventSource::Manual => write!(f, "Manual"),
+        }
+    }
+}
+
+pub enum TimerEventKind {
+    Start,
+    Split,
+    SkipSplit,
+    UndoSplit,
+    Reset,
+    SetGameTime(time::Duration),
+    PauseGameTime,
+    ResumeGameTime,
+    SetVariable { key: String, value: String },
+}
+
+impl TimerEventKind {
+    fn category(&self) -> Category {
+        match self {
+            TimerEventKind::Start
+            | TimerEventKind::Split
+            | TimerEventKind::SkipSplit
+            | TimerEventKind::UndoSplit
+            | TimerEventKind::Reset => Category::Timer,
+            TimerEventKind::SetGameTime(_)
+            | TimerEventKind::PauseGameTime
+            | TimerEventKind::ResumeGameTime => Category::GameTime,
+            TimerEventKind::SetVariable { .. } => Category::Variables,
+        }
+    }
+}
+
+impl fmt::Display for TimerEventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimerEventKind::Start => write!(f, "start"),
+            TimerEventKind::Split => write!(f, "split"),
+            TimerEventKind::SkipSplit => write!(f, "skip_split"),
+            TimerEventKind::UndoSplit => write!(f, "undo_split"),
+            TimerEventKind::Reset => write!(f, "reset"),
+            TimerEventKind::SetGameTime(time) => write!(f, "set_game_time({})", FmtDuration(*time)),
+            TimerEventKind::PauseGameTime => write!(f, "pause_game_time"),
+            TimerEventKind::ResumeGameTime => write!(f, "resume_game_time"),
+            TimerEventKind::SetVariable { key, value } => {
+                write!(f, "set_variable({key:?}, {value:?})")
+            }
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum Category {
+    Timer,
+    GameTime,
+    Variables,
+}
+
+impl Category {
+    const ALL: [Category; 3] = [Category::Timer, Category::GameTime, Category::Variables];
+
+    fn name(self) -> &'static str {
+        match self {
+            Category::Timer => "Timer",
+            Category::GameTime => "Game Time",
+            Category::Variables => "Variables",
+        }
+    }
+}
+
+#[component]
+pub fn Timeline(timer_events: SyncSignal<TimerEvents>) -> Element {
+    let mut hidden = use_signal(Vec::<Category>::new);
+    let mut search = use_signal(String::new);
+
+    let hidden_categories = &*hidden.read();
+    let search_text = search.read().to_lowercase();
+    let events = &*timer_events.read();
+
+    let mut shown = events
+        .entries
+        .iter()
+        .rev()
+        .take(MAX_SCANNED)
+        .filter(|entry| {
+            !hidden_categories.contains(&entry.category)
+                && (search_text.is_empty() || entry.search_text.contains(&search_text))
+        })
+        .take(MAX_SHOWN)
+        .collect::<Vec<_>>();
+    shown.reverse();
+
+    rsx! {
+        div { class: "timeline-filter",
+            for category in Category::ALL {
+                button {
+                    class: if hidden_categories.contains(&category) { "chip" } else { "chip active" },
+                    onclick: move |_| {
+                        let hidden = &mut *hidden.write();
+                        if let Some(index) = hidden.iter().position(|&c| c == category) {
+                            hidden.remove(index);
+                        } else {
+                            hidden.push(category);
+                        }
+                    },
+                    "{category.name()}"
+                }
+            }
+            input {
+                r#type: "search",
+                placeholder: "Filter…",
+                flex_grow: 1,
+                value: "{search}",
+                // The timer shortcuts shouldn't trigger while typing.
+                onkeydown: move |ev| ev.stop_propagation(),
+                oninput: move |ev| search.set(ev.value()),
+            }
+            button {
+                title: "Clear timeline",
+                disabled: events.entries.is_empty(),
+                onclick: move |_| timer_events.write().entries.clear(),
+                MaterialIcon { name: "delete_outline", size: 20 }
+            }
+        }
+        Panel { class: "timeline", flex_grow: "1", min_height: "20px",
+            div { class: "timeline-row gray",
+                div { "Tick" }
+                div { "Time" }
+                div { "Source" }
+                div { "Event" }
+            }
+            for entry in shown {
+                div { class: if entry.applied { "timeline-row" } else { "timeline-row gray" },
+                    title: if !entry.applied { "This call had no effect on the timer." },
+                    div { "{entry.tick}" }
+                    div { "{FmtTime(entry.time)}" }
+                    div { "{entry.source}" }
+                    div { class: if entry.applied { category_class(entry.category) },
+                        "{entry.text}"
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn category_class(category: Category) -> &'static str {
+    match category {
+        Category::Timer => "blue",
+        Category::GameTime => "green",
+        Category::Variables => "pink",
+    }
+}
@@ -1,11 +1,13 @@
-use std::fmt;
+use std::{fmt, time::Duration};
 
-use dioxus::prelude::*;
-use livesplit_auto_splitting::{Timer, TimerState};
+use dioxus::{desktop::window, prelude::*};
+use dioxus_material_icons::MaterialIcon;
+use livesplit_auto_splitting::{LogLevel, Timer, TimerState};
 
 use crate::{
-    ui::{FmtDuration, Widget},
-    GameTimeState, IdeTimer,
+    splits,
+    ui::{FmtDuration, FmtTime, Widget},
+    IdeTimer, TimerModel, UTC_OFFSET,
 };
 
 struct FmtTimerState(TimerState);
@@ -22,41 +24,221 @@ impl fmt::Display for FmtTimerState {
 }
 
 #[component]
-pub fn TimerInfo(
-    split_index: SyncSignal<usize>,
-    timer_state: SyncSignal<TimerState>,
-    game_time: SyncSignal<time::Duration>,
-    game_time_state: SyncSignal<GameTimeState>,
-    timer: SyncSignal<IdeTimer>,
-) -> Element {
-    let is_start = *timer_state.read() == TimerState::NotRunning;
+pub fn TimerInfo(mut timer_model: SyncSignal<TimerModel>, timer: SyncSignal<IdeTimer>) -> Element {
+    let load_splits = move |_| async move {
+        let Some(file) = rfd::AsyncFileDialog::new()
+            .set_parent(&window().window)
+            .add_filter("Splits", &["lss", "txt"])
+            .add_filter("All Files", &["*"])
+            .pick_file()
+            .await
+        else {
+            return;
+        };
+
+        match splits::load(file.path()) {
+            Ok(segments) => timer_model.write().segments = segments,
+            Err(e) => {
+                let mut logs = timer.read().logs;
+                logs.write().push_level(format!("{e:?}"), LogLevel::Error);
+            }
+        }
+    };
+
+    let model = &*timer_model.read();
+    let is_start = model.state == TimerState::NotRunning;
+    let is_paused = model.state == TimerState::Paused;
+    let is_running = model.state == TimerState::Running || is_paused;
+    let rows = model
+        .segments
+        .len()
+        .max(model.splits.len() + is_running as usize);
+
     rsx! {
         Widget { title: "Timer",
             div { class: "table",
                 div { "Timer State" }
-                div { "{FmtTimerState(*timer_state.read())}" }
+                div { "{FmtTimerState(model.state)}" }
+                div { "Real Time" }
+                div {
+                    RealTime { timer_model }
+                }
                 div { "Game Time" }
-                div { "{FmtDuration(*game_time.read())}" }
+                div { "{FmtDuration(model.game_time)}" }
                 div { "Game Time State" }
-                div { "{game_time_state}" }
+                div { "{model.game_time_state}" }
                 div { "Split Index" }
-                div { "{split_index}" }
+                if model.segments.is_empty() {
+                    div { "{model.split_index}" }
+                } else {
+                    div { "{model.split_index} / {model.segments.len()}" }
+                }
+                if let Some(attempt_started) = model.attempt_started {
+                    div { "Started At" }
+                    div { "{FmtTime(attempt_started.to_offset(*UTC_OFFSET.get().unwrap()))}" }
+                }
+            }
+            if rows != 0 {
+                div { class: "split-table",
+                    div { "Segment" }
+                    div { "Real Time" }
+                    div { "Game Time" }
+                    for i in 0..rows {
+                        div {
+                            class: if i == model.split_index && is_running { "blue" },
+                            if let Some(name) = model.segments.get(i) {
+                                "{name}"
+                            } else {
+                                "Split {i + 1}"
+                            }
+                        }
+                        match model.splits.get(i) {
+                            Some(split) => rsx! {
+                                div { class: if !split.splitted { "gray" },
+                                    "{FmtSplitTime(Some(split.real_time))}"
+                                }
+                                div { class: if !split.splitted { "gray" },
+                                    "{FmtSplitTime(split.game_time)}"
+                                }
+                            },
+                            None => rsx! {
+                                div {}
+                                div {}
+                            },
+                        }
+                    }
+                }
+            }
+            div { display: "flex", gap: "5px",
+                button { flex_grow: 1, onclick: load_splits, "Load Splits" }
+                if !model.segments.is_empty() {
+                    button {
+                        flex_grow: 1,
+                        onclick: move |_| timer_model.write().segments.clear(),
+                        "Clear Splits"
+                    }
+                }
             }
-            button {
-                class: if is_start { "start" } else { "stop" },
-                onclick: move |_| {
+            div { class: "timer-controls",
+                button {
+                    class: if is_start { "start" },
+                    title: if is_start { "Start (Numpad 1)" } else { "Split (Numpad 1)" },
+                    disabled: !is_start && model.state != TimerState::Running,
+                    onclick: move |_| start_or_split(&mut timer.write()),
                     if is_start {
-                        timer.write().start();
+                        MaterialIcon { name: "play_arrow", size: 20 }
                     } else {
-                        timer.write().reset();
+                        MaterialIcon { name: "flag", size: 20 }
                     }
-                },
-                if is_start {
-                    "▶"
-                } else {
-                    "⏹"
                 }
+                button {
+                    title: if is_paused { "Resume (Numpad 5)" } else { "Pause (Numpad 5)" },
+                    disabled: !is_running,
+                    onclick: move |_| toggle_pause(&mut timer.write()),
+                    if is_paused {
+                        MaterialIcon { name: "play_arrow", size: 20 }
+                    } else {
+                        MaterialIcon { name: "pause", size: 20 }
+                    }
+                }
+                button {
+                    class: "stop",
+                    title: "Reset (Numpad 3)",
+                    disabled: is_start,
+                    onclick: move |_| timer.write().reset(),
+                    MaterialIcon { name: "stop", size: 20 }
+                }
+                button {
+                    title: "Undo Split (Numpad 8)",
+                    disabled: model.splits.is_empty() || is_paused,
+                    onclick: move |_| timer.write().undo_split(),
+                    MaterialIcon { name: "undo", size: 20 }
+                }
+                button {
+                    title: "Skip Split (Numpad 2)",
+                    disabled: model.state != TimerState::Running,
+                    onclick: move |_| timer.write().skip_split(),
+                    MaterialIcon { name: "skip_next", size: 20 }
+                }
+                button {
+                    title: "Undo All Pauses (Numpad 9)",
+                    disabled: !is_running,
+                    onclick: move |_| timer.write().undo_all_pauses(),
+                    MaterialIcon { name: "restore", size: 20 }
+                }
+            }
+        }
+    }
+}
+
+fn start_or_split(timer: &mut IdeTimer) {
+    if timer.state() == TimerState::NotRunning {
+        timer.start();
+    } else {
+        timer.split();
+    }
+}
+
+fn toggle_pause(timer: &mut IdeTimer) {
+    if timer.state() == TimerState::Paused {
+        timer.resume();
+    } else {
+        timer.pause();
+    }
+}
+
+/// Controls the timer through the same keys that LiveSplit uses as its default
+/// hotkeys. Returns whether the key is one of the shortcuts.
+pub fn handle_timer_shortcut(mut timer: SyncSignal<IdeTimer>, code: &Code) -> bool {
+    let timer = &mut *timer.write();
+    match code {
+        Code::Numpad1 => start_or_split(timer),
+        Code::Numpad2 => timer.skip_split(),
+        Code::Numpad3 => timer.reset(),
+        Code::Numpad5 => toggle_pause(timer),
+        Code::Numpad8 => timer.undo_split(),
+        Code::Numpad9 => timer.undo_all_pauses(),
+        _ => return false,
+    }
+    true
+}
+
+/// Shows the real time of the timer. It refreshes on its own while the timer is
+/// running, as there may be no auto splitter that causes the timer to update.
+#[component]
+fn RealTime(timer_model: SyncSignal<TimerModel>) -> Element {
+    let mut refresh = use_signal(|| ());
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            if timer_model.peek().state == TimerState::Running {
+                refresh.set(());
             }
         }
+    });
+
+    refresh.read();
+    rsx! { "{FmtDuration(timer_model.read().real_time())}" }
+}
+
+/// Formats the time of a split with millisecond precision, so the split table
+/// fits into the side bar.
+struct FmtSplitTime(Option<time::Duration>);
+
+impl fmt::Display for FmtSplitTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(time) = self.0 else {
+            return write!(f, "—");
+        };
+        let total_millis = time.whole_milliseconds().max(0) as u64;
+        let millis = total_millis % 1000;
+        let seconds = (total_millis / 1000) % 60;
+        let minutes = (total_millis / 60_000) % 60;
+        let hours = total_millis / 3_600_000;
+        if hours != 0 {
+            write!(f, "{hours}:{minutes:02}:{seconds:02}.{millis:03}")
+        } else {
+            write!(f, "{minutes}:{seconds:02}.{millis:03}")
+        }
     }
 }
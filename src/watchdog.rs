@@ -0,0 +1,112 @@
+//! Interrupts the auto splitter when a single call of its `update` function
+//! takes too long, which usually means that it is stuck in an infinite loop.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use livesplit_auto_splitting::InterruptHandle;
+
+pub static WATCHDOG: Watchdog = Watchdog::new();
+
+/// How often the watchdog checks whether it should stop or the limit changed.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+pub struct Watchdog {
+    /// The limit in milliseconds, where 0 means that the watchdog is disabled.
+    limit_ms: AtomicU64,
+    current_tick: Mutex<Option<CurrentTick>>,
+}
+
+struct CurrentTick {
+    number: u64,
+    started: Instant,
+    interrupt_handle: InterruptHandle,
+    interrupted: bool,
+}
+
+impl Watchdog {
+    const fn new() -> Self {
+        Self {
+            limit_ms: AtomicU64::new(0),
+            current_tick: Mutex::new(None),
+        }
+    }
+
+    pub fn limit(&self) -> Option<Duration> {
+        match self.limit_ms.load(Ordering::Relaxed) {
+            0 => None,
+            ms => Some(Duration::from_millis(ms)),
+        }
+    }
+
+    pub fn set_limit(&self, limit: Option<Duration>) {
+        let ms = limit.map_or(0, |limit| (limit.as_millis() as u64).max(1));
+        self.limit_ms.store(ms, Ordering::Relaxed);
+    }
+
+    pub fn begin_tick(&self, number: u64, interrupt_handle: InterruptHandle) {
+        *self.current_tick.lock().unwrap() = Some(CurrentTick {
+            number,
+            started: Instant::now(),
+            interrupt_handle,
+            interrupted: false,
+        });
+    }
+
+    pub fn end_tick(&self) {
+        *self.current_tick.lock().unwrap() = None;
+    }
+}
+
+/// Converts a limit in seconds to the limit of the watchdog, where 0 disables
+/// it. Negative, infinite and overly large limits are rejected.
+pub fn limit_from_secs(secs: f64) -> Result<Option<Duration>, String> {
+    if secs == 0.0 {
+        return Ok(None);
+    }
+    Duration::try_from_secs_f64(secs)
+        .map(Some)
+        .map_err(|_| format!("{secs} is not a valid watchdog limit in seconds."))
+}
+
+/// Watches the ticks until the `running` flag is cleared. Whenever a tick
+/// exceeds the limit, it gets interrupted and `on_interrupt` is called with a
+/// message that describes the tick.
+pub fn run(watchdog: &Watchdog, running: &AtomicBool, mut on_interrupt: impl FnMut(String)) {
+    while running.load(Ordering::Relaxed) {
+        let mut sleep_time = POLL_INTERVAL;
+        let mut interrupted = None;
+
+        if let Some(limit) = watchdog.limit() {
+            if let Some(tick) = &mut *watchdog.current_tick.lock().unwrap() {
+                let elapsed = tick.started.elapsed();
+                if tick.interrupted {
+                    // Nothing to do until the next tick begins.
+                } else if elapsed >= limit {
+                    tick.interrupt_handle.interrupt();
+                    tick.interrupted = true;
+                    interrupted = Some((tick.number, elapsed, limit));
+                } else {
+                    sleep_time = sleep_time.min(limit - elapsed);
+                }
+            }
+        }
+
+        if let Some((number, elapsed, limit)) = interrupted {
+            on_interrupt(format!(
+                "Tick {number} has been running for {:.3}s, which exceeds the watchdog \
+                limit of {:.3}s. The auto splitter got interrupted.",
+                elapsed.as_secs_f64(),
+                limit.as_secs_f64(),
+            ));
+        } else {
+            thread::sleep(sleep_time);
+        }
+    }
+}